@@ -1,16 +1,27 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use anyhow::{anyhow, bail};
 
 use crate::{
-    error::syntax_error,
+    error::{runtime_error, TwliError},
     runtime::{Callable, Object},
     token::Token,
 };
 
+#[derive(Debug)]
+struct Binding {
+    value: Object,
+    // set by `const` declarations, assigning to the name is then an error
+    constant: bool,
+}
+
 #[derive(Debug)]
 pub struct Environment {
-    bindings: HashMap<String, Object>,
+    bindings: HashMap<String, Binding>,
     enclosing: Option<Rc<RefCell<Environment>>>,
 }
 
@@ -22,31 +33,119 @@ impl Environment {
         }
     }
 
+    // sized up front for scopes whose binding count is known, like a call's parameters
+    pub fn with_capacity(enclosing: Option<Rc<RefCell<Environment>>>, capacity: usize) -> Self {
+        Self {
+            bindings: HashMap::with_capacity(capacity),
+            enclosing,
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self.bindings.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    pub fn snapshot(&self) -> HashSet<String> {
+        self.bindings.keys().cloned().collect()
+    }
+
+    // names bound since `snapshot` was taken, sorted
+    pub fn diff(&self, snapshot: &HashSet<String>) -> Vec<String> {
+        let mut names = self
+            .bindings
+            .keys()
+            .filter(|name| !snapshot.contains(*name))
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    // the value bound to a name in this scope alone, ignoring enclosing ones
+    pub fn local(&self, key: &str) -> Option<&Object> {
+        self.bindings.get(key).map(|binding| &binding.value)
+    }
+
+    pub fn enclosing(&self) -> Option<Rc<RefCell<Environment>>> {
+        self.enclosing.as_ref().map(Rc::clone)
+    }
+
     pub fn define(&mut self, key: String, value: Object) {
-        self.bindings.insert(key, value);
+        self.bindings.insert(
+            key,
+            Binding {
+                value,
+                constant: false,
+            },
+        );
+    }
+
+    pub fn define_const(&mut self, key: String, value: Object) {
+        self.bindings.insert(
+            key,
+            Binding {
+                value,
+                constant: true,
+            },
+        );
     }
 
-    pub fn define_callable(&mut self, key: String, value: impl Callable + Send + Sync + 'static) {
-        self.bindings.insert(key, Object::Callable(Box::new(value)));
+    pub fn define_callable(&mut self, key: String, value: impl Callable + 'static) {
+        self.define(key, Object::Callable(Box::new(value)));
     }
 
     pub fn get(&self, key: &Token) -> anyhow::Result<Object> {
         match self.bindings.get(&key.lexeme) {
-            Some(obj) => Ok(obj.clone()),
+            Some(binding) => Ok(binding.value.clone()),
             None => match &self.enclosing {
                 Some(enclosing) => RefCell::borrow(enclosing).get(key),
-                None => Err(anyhow!(syntax_error(
-                    &key.line,
-                    &format!("Undefined variable '{}'", key.lexeme)
-                ))),
+                None => Err(undefined(key)),
             },
         }
     }
 
+    // looks a name up exactly `distance` scopes out, as worked out by the resolver
+    pub fn get_at(&self, distance: usize, key: &Token) -> anyhow::Result<Object> {
+        if distance == 0 {
+            return match self.bindings.get(&key.lexeme) {
+                Some(binding) => Ok(binding.value.clone()),
+                None => Err(undefined(key)),
+            };
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => RefCell::borrow(enclosing).get_at(distance - 1, key),
+            None => Err(undefined(key)),
+        }
+    }
+
+    pub fn assign_at(&mut self, distance: usize, key: &str, value: Object) -> anyhow::Result<()> {
+        if distance > 0 {
+            return match &self.enclosing {
+                Some(enclosing) => {
+                    RefCell::borrow_mut(enclosing).assign_at(distance - 1, key, value)
+                }
+                None => bail!(format!("Tried to assign to non-existent binding '{}'", key)),
+            };
+        }
+
+        match self.bindings.get_mut(key) {
+            Some(binding) if binding.constant => bail!("Cannot assign to constant '{}'", key),
+            Some(binding) => {
+                binding.value = value;
+                Ok(())
+            }
+            None => bail!(format!("Tried to assign to non-existent binding '{}'", key)),
+        }
+    }
+
     pub fn assign(&mut self, key: &str, value: Object) -> anyhow::Result<()> {
-        match self.bindings.get(key) {
-            Some(_) => {
-                self.bindings.insert(key.to_string(), value).unwrap();
+        match self.bindings.get_mut(key) {
+            Some(binding) if binding.constant => bail!("Cannot assign to constant '{}'", key),
+            Some(binding) => {
+                binding.value = value;
                 Ok(())
             }
             None => match &self.enclosing {
@@ -56,3 +155,42 @@ impl Environment {
         }
     }
 }
+
+const UNDEFINED: &str = "Undefined variable";
+
+fn undefined(key: &Token) -> anyhow::Error {
+    anyhow!(runtime_error(
+        &key.line,
+        &key.column,
+        &format!("{} '{}'", UNDEFINED, key.lexeme)
+    ))
+}
+
+// whether a lookup failed only because the name isn't bound, for lenient reads
+pub fn is_undefined(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<TwliError>()
+        .is_some_and(|e| e.message().starts_with(UNDEFINED))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_names_bound_since_the_snapshot() {
+        let mut env = Environment::new(None);
+        env.define("old".to_string(), Object::Null);
+        let snapshot = env.snapshot();
+
+        env.define("x".to_string(), Object::Number(1.0));
+        env.define("f".to_string(), Object::Null);
+        env.define("old".to_string(), Object::Boolean(true));
+        assert_eq!(env.diff(&snapshot), ["f", "x"]);
+    }
+
+    #[test]
+    fn diff_is_empty_without_new_names() {
+        let env = Environment::new(None);
+        assert!(env.diff(&env.snapshot()).is_empty());
+    }
+}
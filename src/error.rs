@@ -2,36 +2,136 @@ use std::{error::Error, fmt::Display};
 
 use colored::Colorize;
 
-use crate::runtime::Object;
+// An error tied to a position in the source, kept structured so embedders can inspect
+// it and only turned into colored text when displayed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TwliError {
+    Syntax {
+        line: usize,
+        col: usize,
+        msg: String,
+    },
+    Runtime {
+        line: usize,
+        col: usize,
+        msg: String,
+    },
+}
+
+impl TwliError {
+    pub fn line(&self) -> usize {
+        match self {
+            TwliError::Syntax { line, .. } | TwliError::Runtime { line, .. } => *line,
+        }
+    }
 
-pub fn syntax_error(line: &usize, msg: &str) -> String {
-    format!("\n{} [line {}]: {}.", "SyntaxError".bold().red(), line, msg)
+    pub fn message(&self) -> &str {
+        match self {
+            TwliError::Syntax { msg, .. } | TwliError::Runtime { msg, .. } => msg,
+        }
+    }
 }
 
-pub fn runtime_error(line: &usize, msg: &str) -> String {
-    format!(
-        "\n{} [line {}]: {}.",
-        "RuntimeError".bold().red(),
-        line,
-        msg
-    )
+impl Display for TwliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, line, col, msg) = match self {
+            TwliError::Syntax { line, col, msg } => ("SyntaxError", line, col, msg),
+            TwliError::Runtime { line, col, msg } => ("RuntimeError", line, col, msg),
+        };
+        write!(
+            f,
+            "{} [line {}, column {}]: {}.",
+            kind.bold().red(),
+            line,
+            col,
+            msg
+        )
+    }
 }
 
-#[derive(Debug)]
-pub struct Return {
-    pub value: Option<Object>,
+impl Error for TwliError {}
+
+pub fn syntax_error(line: &usize, column: &usize, msg: &str) -> TwliError {
+    TwliError::Syntax {
+        line: *line,
+        col: *column,
+        msg: msg.to_string(),
+    }
 }
 
-impl Return {
-    pub fn new(value: Option<Object>) -> Self {
-        Self { value }
+pub fn runtime_error(line: &usize, column: &usize, msg: &str) -> TwliError {
+    TwliError::Runtime {
+        line: *line,
+        col: *column,
+        msg: msg.to_string(),
     }
 }
 
-impl Display for Return {
+// Every error a pass collected before giving up. Those past its limit are only
+// counted, in `omitted`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorReport {
+    pub errors: Vec<TwliError>,
+    pub omitted: usize,
+}
+
+impl ErrorReport {
+    // errors that didn't come from `syntax_error`, like a failed conversion, are
+    // attributed to where the pass was at the time
+    pub fn collect(&mut self, e: anyhow::Error, limit: usize, line: usize, col: usize) {
+        if self.errors.len() >= limit {
+            self.omitted += 1;
+            return;
+        }
+
+        let error = match e.downcast::<TwliError>() {
+            Ok(error) => error,
+            Err(e) => syntax_error(&line, &col, &e.to_string()),
+        };
+        self.errors.push(error);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.omitted == 0
+    }
+}
+
+impl Display for ErrorReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors = self
+            .errors
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", errors.join("\n"))?;
+        if self.omitted > 0 {
+            let plural = if self.omitted == 1 { "" } else { "s" };
+            write!(f, "\n... and {} more error{}", self.omitted, plural)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ErrorReport {}
+
+// Control-flow signals travel through the error channel and are matched on by
+// the construct that owns them (e.g. function calls catch `Return`). Objects are
+// not `Send`, so a returned value is parked on the interpreter instead.
+#[derive(Debug)]
+pub enum Signal {
+    Return,
+    Break,
+    Continue,
+}
+
+impl Display for Signal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.value)
+        match self {
+            Signal::Return => write!(f, "return"),
+            Signal::Break => write!(f, "break"),
+            Signal::Continue => write!(f, "continue"),
+        }
     }
 }
 
-impl Error for Return {}
+impl Error for Signal {}
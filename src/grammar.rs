@@ -6,44 +6,124 @@ define! {
     enum declaration -> stmtDecl(StmtDecl)
                         | letDecl(LetDecl)
                         | fnDecl(FnDecl)
-                        | classDecl(ClassDecl);
+                        | classDecl(ClassDecl)
+                        | exportDecl(ExportDecl);
 
-    struct classDecl -> ident(Token), methods(Vec<FnDecl>);
-    struct fnDecl -> ident(Token), params(Vec<Token>), body(Statement);
+    struct exportDecl -> export_token(Token), decl(Box<Declaration>);
+    struct classDecl -> ident(Token), superclass(Option<Token>), methods(Vec<FnDecl>);
+    struct fnDecl -> ident(Token), params(Vec<Token>), variadic(bool), body(Statement);
     struct stmtDecl -> stmt(Statement);
-    struct letDecl -> ident(Token), init(Option<Expression>);
+    struct letDecl -> ident(Token), init(Option<Expression>), constant(bool);
 
     enum statement -> exprStmt(ExprStmt)
                         | blockStmt(BlockStmt)
                         | ifStmt(IfStmt)
                         | whileStmt(WhileStmt)
-                        | returnStmt(ReturnStmt);
+                        | returnStmt(ReturnStmt)
+                        | breakStmt(BreakStmt)
+                        | continueStmt(ContinueStmt)
+                        | importStmt(ImportStmt)
+                        | tryStmt(TryStmt);
 
+    struct tryStmt -> try_token(Token), body(Box<Statement>), catch_ident(Option<Token>), catch_body(Option<Box<Statement>>), finally_body(Option<Box<Statement>>);
+    struct importStmt -> import_token(Token), path(String), alias(Option<Token>);
     struct ReturnStmt -> return_token(Token), expr(Option<Expression>);
-    struct whileStmt -> condition(Expression), body(Box<Statement>);
+    struct breakStmt -> break_token(Token);
+    struct continueStmt -> continue_token(Token);
+    struct whileStmt -> condition(Expression), body(Box<Statement>), increment(Option<Expression>);
     struct ifStmt -> condition(Expression), if_branch(Box<Statement>), else_branch(Option<Box<Statement>>);
     struct exprStmt -> expr(Expression);
     struct BlockStmt -> stmts(Vec<Declaration>);
 
     enum expression ->  literal(Literal)
-                        | var(Token)
+                        | var(Variable)
+                        | this(Token)
+                        | super(Super)
                         | call(Call)
                         | get(Get)
+                        | index(Index)
+                        | setIndex(SetIndex)
+                        | list(ListLiteral)
+                        | comprehension(Comprehension)
                         | unary(Unary)
                         | set(Set)
                         | logical(Logical)
                         | binary(Binary)
                         | range(Range)
                         | grouping(Box<Expression>)
+                        | lambda(Box<FnDecl>)
+                        | object(ObjectLiteral)
+                        | map(MapLiteral)
                         | assignment(Assignment);
 
-    struct assignment -> ident(Token), expr(Box<Expression>);
-    struct range -> left(Box<Expression>), right(Box<Expression>);
+    struct variable -> ident(Token), resolution(Resolution);
+    struct assignment -> ident(Token), expr(Box<Expression>), resolution(Resolution);
+    struct range -> left(Box<Expression>), operator(Token), right(Box<Expression>);
     struct binary -> left(Box<Expression>), operator(Token), right(Box<Expression>);
     struct logical ->left(Box<Expression>), operator(Token), right(Box<Expression>);
     struct set -> object(Box<Expression>), field(Token), value(Box<Expression>);
     struct unary -> operator(Token), expr(Box<Expression>);
     struct get -> object(Box<Expression>), field(Token);
+    struct index -> object(Box<Expression>), bracket_token(Token), index(Box<Expression>);
+    struct setIndex -> object(Box<Expression>), bracket_token(Token), index(Box<Expression>), value(Box<Expression>), operator(Option<Token>);
+    struct listLiteral -> bracket_token(Token), items(Vec<Expression>);
+    struct comprehension -> bracket_token(Token), expr(Box<Expression>), variable(Token), iterable(Box<Expression>), condition(Option<Box<Expression>>);
+    struct super -> keyword(Token), method(Token);
+    struct objectLiteral -> brace_token(Token), fields(Vec<ObjectField>);
+    struct objectField -> ident(Token), value(Expression);
+    struct mapLiteral -> brace_token(Token), entries(Vec<MapEntry>);
+    struct mapEntry -> key(String), value(Expression);
     struct call -> callee(Box<Expression>), paren_token(Token), args(Vec<Expression>);
     enum literal -> boolean(bool) | number(f64) | str(String) | null;
 }
+
+// Where a variable lives, filled in by the resolver; code it never saw, like `eval`'d
+// source, is left unresolved and looked up by walking the scope chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Unresolved,
+    // the number of scopes between the use and the one declaring the name
+    Local(usize),
+    Global,
+}
+
+impl Declaration {
+    // the name a declaration binds, if any
+    pub fn ident(&self) -> Option<&Token> {
+        match self {
+            Declaration::StmtDecl(_) => None,
+            Declaration::LetDecl(let_decl) => Some(&let_decl.ident),
+            Declaration::FnDecl(fn_decl) => Some(&fn_decl.ident),
+            Declaration::ClassDecl(class_decl) => Some(&class_decl.ident),
+            Declaration::ExportDecl(export_decl) => export_decl.decl.ident(),
+        }
+    }
+}
+
+impl Expression {
+    // Literals carry no token, so they are the only expressions without a position.
+    pub fn token(&self) -> Option<&Token> {
+        match self {
+            Expression::Literal(_) => None,
+            Expression::Var(var) => Some(&var.ident),
+            Expression::This(token) => Some(token),
+            Expression::Super(sup) => Some(&sup.keyword),
+            Expression::Call(call) => Some(&call.paren_token),
+            Expression::Get(get) => Some(&get.field),
+            Expression::Index(index) => Some(&index.bracket_token),
+            Expression::SetIndex(set) => Some(&set.bracket_token),
+            Expression::List(list) => Some(&list.bracket_token),
+            Expression::Comprehension(comp) => Some(&comp.bracket_token),
+            Expression::Unary(unary) => Some(&unary.operator),
+            Expression::Set(set) => Some(&set.field),
+            Expression::Logical(logical) => Some(&logical.operator),
+            Expression::Binary(binary) => Some(&binary.operator),
+            Expression::Range(range) => Some(&range.operator),
+            Expression::Grouping(expression) => expression.token(),
+            Expression::Lambda(lambda) => Some(&lambda.ident),
+            Expression::Object(object) => Some(&object.brace_token),
+            Expression::Map(map) => Some(&map.brace_token),
+            Expression::Assignment(assignment) => Some(&assignment.ident),
+        }
+    }
+}
@@ -1,84 +1,582 @@
-use std::{cell::RefCell, cmp::Ordering, rc::Rc};
+use std::{
+    cell::RefCell, cmp::Ordering, collections::HashMap, fs::read_to_string, mem, path::PathBuf,
+    rc::Rc,
+};
 
 use anyhow::{anyhow, bail};
 
 use crate::{
-    env::Environment,
-    error::{runtime_error, Return},
+    env::{is_undefined, Environment},
+    error::{runtime_error, ErrorReport, Signal, TwliError},
     grammar::{
-        Assignment, Binary, BlockStmt, Call, ClassDecl, Declaration, ExprStmt, Expression, FnDecl,
-        Get, IfStmt, LetDecl, Literal, Logical, Range, ReturnStmt, Set, Statement, Unary,
-        WhileStmt,
+        Assignment, Binary, BlockStmt, Call, ClassDecl, Comprehension, Declaration, ExprStmt,
+        Expression, FnDecl, Get, IfStmt, ImportStmt, Index, LetDecl, ListLiteral, Literal, Logical,
+        MapLiteral, ObjectLiteral, Range, Resolution, ReturnStmt, Set, SetIndex, Statement,
+        StmtDecl, Super, TryStmt, Unary, Variable, WhileStmt,
+    },
+    lexer::Lexer,
+    parser::Parser,
+    resolver::Resolver,
+    runtime::{
+        slice_bounds, str_len, BoundMethod, Callable, Class, Function, Instance, Module, Object,
+        Superclass,
     },
-    runtime::{Class, Function, Object},
-    std::Println,
-    token::TokenType,
+    std::{
+        Assert, AssertThrows, BinaryMath, Breakpoint, Builtin, Chunk, Clock, Collect, Contains,
+        DebugEnv, Eval, Flatten, GroupBy, Help, Input, Len, Memoize, Print, Println, Random,
+        RandomInt, ReduceRight, SatAdd, SatSub, Scan, Seed, Sort, Split, Stringify, ToNumber,
+        TypeOf, UnaryMath, UnaryStr, Unique, Windows,
+    },
+    token::{Token, TokenType},
 };
 
 pub struct Interpreter {
     pub global: Rc<RefCell<Environment>>,
     pub current: Rc<RefCell<Environment>>,
+    pub trace: bool,
+    pub debug: bool,
+    // reading an undefined variable yields null instead of an error
+    pub lenient: bool,
+    // disables builtins that run arbitrary code, such as `eval`
+    pub sandbox: bool,
+    // a runtime error skips to the next top-level declaration, every error being
+    // reported once the program ends
+    pub keep_going: bool,
+    // whole numbers are displayed with a trailing `.0` (`3.0` instead of `3`)
+    pub strict_float: bool,
+    // `/` between whole numbers truncates toward zero, leaving `/.` for exact division
+    pub int_div: bool,
+    // upper bound on values built at once, by repetition (`"ab" * 3`) or `collect`:
+    // bytes of a string, items of a list
+    pub max_result_size: usize,
+    // xorshift64 state of the random builtins, zero until first seeded
+    pub random_state: u64,
+    // files currently being executed, the innermost import last
+    pub files: Vec<PathBuf>,
+    pub return_value: Option<Object>,
+    // names exported by the file currently being executed
+    exports: Vec<String>,
+    // metadata of every builtin defined in the global scope, in definition order
+    pub builtins: Vec<Builtin>,
+    // the builtins alone, enclosing the top-level scope of every imported file
+    prelude: Rc<RefCell<Environment>>,
+    // line and column of the call being made, for builtins that report where they were called
+    pub call_site: (usize, usize),
+    depth: usize,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
         let global = Rc::new(RefCell::new(Environment::new(None)));
-        let mut borrow = RefCell::borrow_mut(&global);
-        borrow.define_callable("println".to_string(), Println {});
-        Self {
+        let mut interp = Self {
             global: Rc::clone(&global),
             current: Rc::clone(&global),
-        }
+            trace: false,
+            debug: false,
+            lenient: false,
+            sandbox: false,
+            keep_going: false,
+            strict_float: false,
+            int_div: false,
+            max_result_size: 1 << 24,
+            random_state: 0,
+            files: Vec::new(),
+            return_value: None,
+            exports: Vec::new(),
+            builtins: Vec::new(),
+            prelude: Rc::new(RefCell::new(Environment::new(None))),
+            call_site: (0, 0),
+            depth: 0,
+        };
+
+        interp.define_builtin(
+            "println",
+            "Prints a value followed by a newline",
+            Println {},
+        );
+        interp.define_builtin(
+            "print",
+            "Prints a value without a trailing newline",
+            Print {},
+        );
+        interp.define_builtin(
+            "input",
+            "Reads a line from stdin after printing an optional prompt, null at end of input",
+            Input {},
+        );
+        interp.define_builtin("len", "Returns the length of a string, list or map", Len {});
+        interp.define_builtin(
+            "typeof",
+            "Returns the name of the type of a value, such as \"number\" or \"instance\"",
+            TypeOf {},
+        );
+        interp.define_builtin(
+            "to_number",
+            "Parses a string into a number, null if it isn't one, numbers are returned as they are",
+            ToNumber {},
+        );
+        interp.define_builtin(
+            "to_string",
+            "Converts any value into the string it prints as",
+            Stringify {},
+        );
+        interp.define_builtin(
+            "sqrt",
+            "Returns the square root of a number",
+            UnaryMath {
+                name: "sqrt",
+                op: f64::sqrt,
+            },
+        );
+        interp.define_builtin(
+            "abs",
+            "Returns the absolute value of a number",
+            UnaryMath {
+                name: "abs",
+                op: f64::abs,
+            },
+        );
+        interp.define_builtin(
+            "floor",
+            "Rounds a number down to an integer",
+            UnaryMath {
+                name: "floor",
+                op: f64::floor,
+            },
+        );
+        interp.define_builtin(
+            "ceil",
+            "Rounds a number up to an integer",
+            UnaryMath {
+                name: "ceil",
+                op: f64::ceil,
+            },
+        );
+        interp.define_builtin(
+            "round",
+            "Rounds a number to the nearest integer, halves away from zero",
+            UnaryMath {
+                name: "round",
+                op: f64::round,
+            },
+        );
+        interp.define_builtin(
+            "pow",
+            "Raises the first number to the power of the second",
+            BinaryMath {
+                name: "pow",
+                op: f64::powf,
+            },
+        );
+        interp.define_builtin(
+            "min",
+            "Returns the smaller of two numbers",
+            BinaryMath {
+                name: "min",
+                op: f64::min,
+            },
+        );
+        interp.define_builtin(
+            "max",
+            "Returns the larger of two numbers",
+            BinaryMath {
+                name: "max",
+                op: f64::max,
+            },
+        );
+        interp.define_builtin(
+            "sat_add",
+            "Adds two numbers, clamping the sum to the maximum given as the third",
+            SatAdd {},
+        );
+        interp.define_builtin(
+            "sat_sub",
+            "Subtracts two numbers, clamping the difference to the minimum given as the third",
+            SatSub {},
+        );
+        interp.define_builtin(
+            "upper",
+            "Returns a string with every letter in uppercase",
+            UnaryStr {
+                name: "upper",
+                op: str::to_uppercase,
+            },
+        );
+        interp.define_builtin(
+            "lower",
+            "Returns a string with every letter in lowercase",
+            UnaryStr {
+                name: "lower",
+                op: str::to_lowercase,
+            },
+        );
+        interp.define_builtin(
+            "trim",
+            "Returns a string without leading and trailing whitespace",
+            UnaryStr {
+                name: "trim",
+                op: |s| s.trim().to_string(),
+            },
+        );
+        interp.define_builtin(
+            "split",
+            "Splits a string on a separator into a list of strings",
+            Split {},
+        );
+        interp.define_builtin(
+            "contains",
+            "Returns whether a string contains another",
+            Contains {},
+        );
+        interp.define_builtin(
+            "collect",
+            "Materializes a range into a list, lists are returned as they are",
+            Collect {},
+        );
+        interp.define_builtin(
+            "sort",
+            "Returns a sorted copy of a list, comparing strings as numbers when the flag is set",
+            Sort {},
+        );
+        interp.define_builtin(
+            "reduce_right",
+            "Folds a list from its last item, calling the function with the accumulator and each item",
+            ReduceRight {},
+        );
+        interp.define_builtin(
+            "scan",
+            "Folds a list from its first item, returning the list of every accumulator value",
+            Scan {},
+        );
+        interp.define_builtin(
+            "group_by",
+            "Groups the items of a list into a map from the key the function returns for each",
+            GroupBy {},
+        );
+        interp.define_builtin(
+            "unique",
+            "Returns a copy of a list without repeated items, keeping the first of each",
+            Unique {},
+        );
+        interp.define_builtin(
+            "flatten",
+            "Concatenates the lists nested in a list, passing other items through",
+            Flatten { deep: false },
+        );
+        interp.define_builtin(
+            "flatten_deep",
+            "Concatenates the lists nested in a list at any depth, passing other items through",
+            Flatten { deep: true },
+        );
+        interp.define_builtin(
+            "chunk",
+            "Splits a list into consecutive lists of the given size, the last possibly shorter",
+            Chunk {},
+        );
+        interp.define_builtin(
+            "windows",
+            "Returns every overlapping run of the given size in a list",
+            Windows {},
+        );
+        interp.define_builtin(
+            "memoize",
+            "Wraps a function so it runs once per set of arguments, reusing the cached result",
+            Memoize {},
+        );
+        interp.define_builtin(
+            "clock",
+            "Returns the seconds since the Unix epoch, with a fractional part",
+            Clock {},
+        );
+        interp.define_builtin(
+            "random",
+            "Returns a uniformly distributed number in [0, 1)",
+            Random {},
+        );
+        interp.define_builtin(
+            "random_int",
+            "Returns a random integer between two integers, both included",
+            RandomInt {},
+        );
+        interp.define_builtin(
+            "seed",
+            "Resets the random number generator so the numbers it returns repeat",
+            Seed {},
+        );
+        interp.define_builtin(
+            "breakpoint",
+            "Pauses execution and opens a prompt when running with --debug",
+            Breakpoint {},
+        );
+        interp.define_builtin(
+            "debug_env",
+            "Lists the names bound in each scope, from the innermost out to the global one",
+            DebugEnv {},
+        );
+        interp.define_builtin(
+            "assert",
+            "Raises an error, with the optional message, when the condition is falsy",
+            Assert {},
+        );
+        interp.define_builtin(
+            "assert_throws",
+            "Calls a function with no arguments, returning the error it raises",
+            AssertThrows {},
+        );
+        interp.define_builtin(
+            "eval",
+            "Runs a string of source code, returning the value of its final expression",
+            Eval {},
+        );
+        interp.define_builtin(
+            "help",
+            "Lists the builtins, or describes the one named by the argument",
+            Help {},
+        );
+        interp
+    }
+
+    fn define_builtin(&mut self, name: &str, description: &str, builtin: impl Callable + 'static) {
+        self.builtins.push(Builtin {
+            name: name.to_string(),
+            arity: builtin.arity(),
+            max_arity: builtin.max_arity(),
+            description: description.to_string(),
+        });
+        RefCell::borrow_mut(&self.prelude)
+            .define(name.to_string(), Object::Callable(builtin.clone_box()));
+        RefCell::borrow_mut(&self.global).define_callable(name.to_string(), builtin);
     }
 
     pub fn interpret(&mut self, ast: Vec<Declaration>) -> anyhow::Result<()> {
+        let mut errors = ErrorReport::default();
         for stmt in ast.iter() {
-            self.register_declaration(stmt)?
+            if let Err(e) = self.register_declaration(stmt) {
+                if !self.keep_going {
+                    return Err(e);
+                }
+                errors.errors.push(self.located(e));
+            }
+        }
+
+        if let Err(e) = self.call_entry_point(&ast) {
+            if !self.keep_going {
+                return Err(e);
+            }
+            errors.errors.push(self.located(e));
+        }
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        bail!(errors)
+    }
+
+    // errors raised without a position, such as a builtin's, are placed at the last call
+    fn located(&self, e: anyhow::Error) -> TwliError {
+        e.downcast::<TwliError>().unwrap_or_else(|e| {
+            let (line, column) = self.call_site;
+            runtime_error(&line, &column, e.to_string().trim())
+        })
+    }
+
+    // the names bound in every scope between the current one and the global one,
+    // outermost first, for resolving source run where execution currently is
+    pub fn local_scopes(&self) -> Vec<Vec<String>> {
+        let mut scopes = Vec::new();
+        let mut scope = Rc::clone(&self.current);
+        while !Rc::ptr_eq(&scope, &self.global) {
+            scopes.push(RefCell::borrow(&scope).names());
+            let Some(enclosing) = RefCell::borrow(&scope).enclosing() else {
+                break;
+            };
+            scope = enclosing;
+        }
+        scopes.reverse();
+        scopes
+    }
+
+    // a top-level `fn main()` is the entry point, called once every
+    // declaration is registered so it can use anything defined after it
+    fn call_entry_point(&mut self, ast: &[Declaration]) -> anyhow::Result<()> {
+        let main = ast.iter().find_map(|decl| match decl {
+            Declaration::FnDecl(fn_decl) if fn_decl.ident.lexeme == "main" => Some(fn_decl),
+            _ => None,
+        });
+
+        if let Some(main) = main {
+            let ident = &main.ident;
+            if !main.params.is_empty() {
+                bail!(runtime_error(
+                    &ident.line,
+                    &ident.column,
+                    "Entry point 'main' must not take parameters"
+                ))
+            }
+
+            let callee = RefCell::borrow(&self.current).get(ident)?;
+            if let Object::Callable(mut c) = callee {
+                c.call(self, Vec::new())?;
+            }
         }
 
         Ok(())
     }
 
+    // runs the declarations in the current scope, yielding the value of a
+    // trailing expression statement (null otherwise)
+    pub fn interpret_value(&mut self, mut ast: Vec<Declaration>) -> anyhow::Result<Object> {
+        let last = match ast.last() {
+            Some(Declaration::StmtDecl(StmtDecl {
+                stmt: Statement::ExprStmt(_),
+            })) => ast.pop(),
+            _ => None,
+        };
+
+        for decl in ast.iter() {
+            self.register_declaration(decl)?
+        }
+
+        match last {
+            Some(Declaration::StmtDecl(StmtDecl {
+                stmt: Statement::ExprStmt(expr_stmt),
+            })) => self.eval_expression(&expr_stmt.expr),
+            _ => Ok(Object::Null),
+        }
+    }
+
+    // a value as the program prints it, following the display flags
+    pub fn show(&self, value: &Object) -> String {
+        value.display(self.strict_float)
+    }
+
+    pub fn trace_enter(&mut self, ident: &str, args: &[Object]) {
+        if !self.trace {
+            return;
+        }
+
+        let args = args
+            .iter()
+            .map(|arg| self.show(arg))
+            .collect::<Vec<_>>()
+            .join(", ");
+        eprintln!("{}-> {}({})", "  ".repeat(self.depth), ident, args);
+        self.depth += 1;
+    }
+
+    pub fn trace_exit(&mut self, ident: &str, result: &anyhow::Result<Object>) {
+        if !self.trace {
+            return;
+        }
+
+        self.depth -= 1;
+        let indent = "  ".repeat(self.depth);
+        match result {
+            Ok(value) => eprintln!("{}<- {} = {}", indent, ident, self.show(value)),
+            Err(_) => eprintln!("{}<- {} failed", indent, ident),
+        }
+    }
+
     fn register_declaration(&mut self, decl: &Declaration) -> anyhow::Result<()> {
         match decl {
             Declaration::StmtDecl(stmt_decl) => self.exec_statement(&stmt_decl.stmt),
             Declaration::LetDecl(let_decl) => self.register_let_declaration(let_decl),
             Declaration::FnDecl(fn_decl) => self.register_function_declaration(fn_decl),
             Declaration::ClassDecl(class_decl) => self.register_class_declaration(class_decl),
+            Declaration::ExportDecl(export_decl) => {
+                self.register_declaration(&export_decl.decl)?;
+                if let Some(ident) = export_decl.decl.ident() {
+                    self.exports.push(ident.lexeme.clone());
+                }
+                Ok(())
+            }
         }
     }
 
     fn register_class_declaration(&mut self, class_decl: &ClassDecl) -> anyhow::Result<()> {
         let ident = class_decl.ident.lexeme.clone();
         RefCell::borrow_mut(&self.current).define(ident.clone(), Object::Null);
+
+        let superclass = class_decl.superclass.as_ref().map(|parent| {
+            let class = match RefCell::borrow(&self.current).get(parent) {
+                Ok(Object::Callable(c)) => c.class(),
+                _ => None,
+            };
+            match class {
+                Some(class) => Superclass::Class(Rc::new(class)),
+                None => Superclass::Unresolved(parent.clone()),
+            }
+        });
+
+        // methods of a subclass close over a scope binding `super` to the superclass.
+        // The scope exists even when the superclass is unresolved, keeping the
+        // distances the resolver computed for the methods' variables
+        let closure = match &superclass {
+            Some(superclass) => {
+                let mut env = Environment::new(Some(Rc::clone(&self.current)));
+                if let Superclass::Class(class) = superclass {
+                    env.define_callable("super".to_string(), class.as_ref().clone());
+                }
+                Rc::new(RefCell::new(env))
+            }
+            None => Rc::clone(&self.current),
+        };
+
+        let methods = class_decl
+            .methods
+            .iter()
+            .map(|method| {
+                let function = Function {
+                    declaration: method.clone(),
+                    closure: Rc::clone(&closure),
+                };
+                (method.ident.lexeme.clone(), function)
+            })
+            .collect();
+
         RefCell::borrow_mut(&self.current).assign(
             &ident,
             Object::Callable(Box::new(Class {
                 ident: ident.clone(),
+                methods: Rc::new(methods),
+                superclass,
             })),
         )
     }
 
     fn register_function_declaration(&mut self, fn_decl: &FnDecl) -> anyhow::Result<()> {
-        RefCell::borrow_mut(&mut self.current).define_callable(
-            fn_decl.ident.lexeme.clone(),
-            Function {
-                declaration: fn_decl.clone(),
-            },
-        );
+        let function = Function {
+            declaration: fn_decl.clone(),
+            closure: Rc::clone(&self.current),
+        };
+        RefCell::borrow_mut(&self.current).define_callable(fn_decl.ident.lexeme.clone(), function);
 
         Ok(())
     }
 
     fn register_let_declaration(&mut self, let_decl: &LetDecl) -> anyhow::Result<()> {
-        match &let_decl.init {
-            Some(i) => {
-                let init = self.eval_expression(&i)?;
-                Ok(RefCell::borrow_mut(&self.global).define(let_decl.ident.lexeme.clone(), init))
+        // `let _ = expr;` evaluates the initializer but binds nothing
+        if let_decl.ident.lexeme == "_" {
+            if let Some(i) = &let_decl.init {
+                self.eval_expression(i)?;
             }
-            None => Ok(RefCell::borrow_mut(&self.global)
-                .define(let_decl.ident.lexeme.clone(), Object::Null)),
+            return Ok(());
         }
+
+        let init = match &let_decl.init {
+            Some(i) => self.eval_expression(&i)?,
+            None => Object::Null,
+        };
+
+        let ident = let_decl.ident.lexeme.clone();
+        let mut env = RefCell::borrow_mut(&self.current);
+        if let_decl.constant {
+            env.define_const(ident, init);
+        } else {
+            env.define(ident, init);
+        }
+        Ok(())
     }
 
     fn exec_statement(&mut self, stmt: &Statement) -> anyhow::Result<()> {
@@ -93,21 +591,138 @@ impl Interpreter {
             Statement::IfStmt(if_stmt) => self.exec_if_statement(if_stmt),
             Statement::WhileStmt(while_stmt) => self.exec_while_statement(while_stmt),
             Statement::ReturnStmt(return_stmt) => self.exec_return_statement(return_stmt),
+            Statement::BreakStmt(_) => Err(anyhow::Error::new(Signal::Break)),
+            Statement::ContinueStmt(_) => Err(anyhow::Error::new(Signal::Continue)),
+            Statement::ImportStmt(import_stmt) => self.exec_import_statement(import_stmt),
+            Statement::TryStmt(try_stmt) => self.exec_try_statement(try_stmt),
         }
     }
 
+    fn exec_import_statement(&mut self, import_stmt: &ImportStmt) -> anyhow::Result<()> {
+        let token = &import_stmt.import_token;
+        let import_error = |msg: String| anyhow!(runtime_error(&token.line, &token.column, &msg));
+
+        // paths are resolved relative to the importing file
+        let path = match self.files.last().and_then(|file| file.parent()) {
+            Some(dir) => dir.join(&import_stmt.path),
+            None => PathBuf::from(&import_stmt.path),
+        };
+        let path = path
+            .canonicalize()
+            .map_err(|e| import_error(format!("Cannot import '{}': {}", import_stmt.path, e)))?;
+
+        if self.files.contains(&path) {
+            return Err(import_error(format!(
+                "Cyclic import of '{}'",
+                import_stmt.path
+            )));
+        }
+
+        let source = read_to_string(&path)
+            .map_err(|e| import_error(format!("Cannot import '{}': {}", import_stmt.path, e)))?;
+        let tokens = Lexer::new(source.trim().to_string()).tokenize()?;
+        let mut declarations = Parser::new(tokens).parse()?;
+        Resolver::new().resolve(&mut declarations)?;
+
+        // every imported file runs in its own top-level scope, seeing the builtins but
+        // none of the importer's globals. Declarations are private to that file unless
+        // marked with `export`
+        let scope = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(
+            &self.prelude,
+        )))));
+
+        self.files.push(path);
+        let previous_exports = mem::take(&mut self.exports);
+        let previous_global = mem::replace(&mut self.global, Rc::clone(&scope));
+        let previous = mem::replace(&mut self.current, Rc::clone(&scope));
+        let result = declarations
+            .iter()
+            .try_for_each(|decl| self.register_declaration(decl));
+        self.current = previous;
+        self.global = previous_global;
+        let exports = mem::replace(&mut self.exports, previous_exports);
+        self.files.pop();
+        result?;
+
+        let mut members = HashMap::new();
+        for ident in exports {
+            if let Some(value) = RefCell::borrow(&scope).local(&ident) {
+                members.insert(ident, value.clone());
+            }
+        }
+
+        // a plain import adds the exported names to the global scope, an aliased
+        // one binds them as the members of a module object
+        match &import_stmt.alias {
+            Some(alias) => {
+                let module = Module::new(alias.lexeme.clone(), members);
+                RefCell::borrow_mut(&self.current)
+                    .define(alias.lexeme.clone(), Object::Module(Rc::new(module)));
+            }
+            None => {
+                for (ident, value) in members {
+                    RefCell::borrow_mut(&self.global).define(ident, value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn exec_return_statement(&mut self, return_stmt: &ReturnStmt) -> anyhow::Result<()> {
         let mut value = None;
         if let Some(e) = &return_stmt.expr {
             value = Some(self.eval_expression(&e)?);
         }
 
-        Err(anyhow::Error::new(Return::new(value)))
+        self.return_value = value;
+        Err(anyhow::Error::new(Signal::Return))
+    }
+
+    fn exec_try_statement(&mut self, try_stmt: &TryStmt) -> anyhow::Result<()> {
+        let mut result = self.exec_statement(&try_stmt.body);
+
+        // control flow signals are not errors, they pass through `catch` untouched
+        if let (Err(e), Some(catch_body)) = (&result, &try_stmt.catch_body) {
+            if !e.is::<Signal>() {
+                let mut env = Environment::new(Some(Rc::clone(&self.current)));
+                if let Some(ident) = &try_stmt.catch_ident {
+                    let msg = e.to_string().trim().to_string();
+                    env.define(ident.lexeme.clone(), Object::Str(msg));
+                }
+                if let Statement::BlockStmt(block) = catch_body.as_ref() {
+                    result = self.exec_block_statement(block, Rc::new(RefCell::new(env)));
+                }
+            }
+        }
+
+        // `finally` runs however the try and catch blocks were left, including by a
+        // `return`, whose value is parked while it runs and restored afterwards; a
+        // signal or error raised by `finally` itself replaces the pending outcome
+        if let Some(finally_body) = &try_stmt.finally_body {
+            let returned = self.return_value.take();
+            self.exec_statement(finally_body)?;
+            self.return_value = returned;
+        }
+
+        result
     }
 
     fn exec_while_statement(&mut self, while_stmt: &WhileStmt) -> anyhow::Result<()> {
         while self.eval_expression(&while_stmt.condition)?.thrutiness() {
-            self.exec_statement(&while_stmt.body)?;
+            if let Err(e) = self.exec_statement(&while_stmt.body) {
+                match e.downcast::<Signal>() {
+                    Ok(Signal::Break) => break,
+                    Ok(Signal::Continue) => (),
+                    Ok(signal) => return Err(signal.into()),
+                    Err(e) => return Err(e),
+                }
+            }
+
+            // a for loop's increment also runs after `continue`
+            if let Some(increment) = &while_stmt.increment {
+                self.eval_expression(increment)?;
+            }
         }
 
         Ok(())
@@ -134,12 +749,14 @@ impl Interpreter {
         let previous = Rc::clone(&self.current);
         self.current = new_env;
 
-        for decl in block_stmt.stmts.iter() {
-            self.register_declaration(decl)?
-        }
+        // restore the previous scope even when a signal or error unwinds the block
+        let result = block_stmt
+            .stmts
+            .iter()
+            .try_for_each(|decl| self.register_declaration(decl));
 
         self.current = previous;
-        Ok(())
+        result
     }
 
     fn exec_expression_statement(&mut self, expr_stmt: &ExprStmt) -> anyhow::Result<()> {
@@ -150,12 +767,25 @@ impl Interpreter {
     fn eval_expression(&mut self, expr: &Expression) -> anyhow::Result<Object> {
         match expr {
             Expression::Literal(literal) => self.eval_literal(literal),
-            Expression::Var(token) => RefCell::borrow_mut(&self.current).get(&token),
+            Expression::This(token) => RefCell::borrow(&self.current).get(&token),
+            Expression::Super(sup) => self.eval_super(sup),
+            // lenient mode reads an undefined name as null, resolved like any other read
+            Expression::Var(var) => match self.lookup_variable(var) {
+                Err(e) if self.lenient && is_undefined(&e) => Ok(Object::Null),
+                result => result,
+            },
             Expression::Call(call) => self.eval_call(call),
             Expression::Unary(unary) => self.eval_unary(unary),
             Expression::Binary(binary) => self.eval_binary(binary),
             Expression::Logical(logical) => self.eval_logical(logical),
             Expression::Range(range) => self.eval_range(range),
+            Expression::Lambda(lambda) => self.eval_lambda(lambda),
+            Expression::Object(object) => self.eval_object(object),
+            Expression::List(list) => self.eval_list(list),
+            Expression::Comprehension(comp) => self.eval_comprehension(comp),
+            Expression::Map(map) => self.eval_map(map),
+            Expression::Index(index) => self.eval_index(index),
+            Expression::SetIndex(set) => self.eval_set_index(set),
             Expression::Grouping(expression) => self.eval_expression(expression),
             Expression::Assignment(assignment) => self.eval_assignment(assignment),
             Expression::Get(get) => self.eval_get(get),
@@ -167,15 +797,17 @@ impl Interpreter {
         let obj = self.eval_expression(&set.object)?;
 
         if !matches!(obj, Object::Instance(_)) {
+            let token = set.object.token().unwrap_or(&set.field);
             bail!(runtime_error(
-                &set.field.line,
+                &token.line,
+                &token.column,
                 "Only class instances have fields"
             ))
         }
 
-        if let Object::Instance(mut i) = obj {
+        if let Object::Instance(i) = obj {
             let value = self.eval_expression(&set.value)?;
-            i.set(set.field.clone(), value.clone());
+            RefCell::borrow_mut(&i).set(set.field.clone(), value.clone());
             return Ok(value);
         }
 
@@ -184,26 +816,259 @@ impl Interpreter {
 
     fn eval_get(&mut self, get: &Get) -> anyhow::Result<Object> {
         let obj = self.eval_expression(&get.object)?;
-        if let Object::Instance(inst) = obj {
-            return inst.get(&get.field);
+        if let Object::Instance(inst) = &obj {
+            let method = inst.borrow().method(&get.field.lexeme);
+            if let Some(method) = method {
+                return Ok(Object::Callable(Box::new(BoundMethod {
+                    receiver: obj.clone(),
+                    method,
+                })));
+            }
+            return inst.borrow().get(&get.field);
+        }
+        if let Object::Module(module) = obj {
+            return module.get(&get.field);
         }
+        let token = get.object.token().unwrap_or(&get.field);
         bail!(runtime_error(
-            &get.field.line,
+            &token.line,
+            &token.column,
             "Only class instances have fields"
         ))
     }
 
+    fn lookup_variable(&self, var: &Variable) -> anyhow::Result<Object> {
+        match var.resolution {
+            Resolution::Local(distance) => {
+                RefCell::borrow(&self.current).get_at(distance, &var.ident)
+            }
+            // a name no enclosing scope declared when the use was resolved skips any
+            // local declared since, unless it isn't global at all, like a function
+            // declared further down the block
+            Resolution::Global => RefCell::borrow(&self.global)
+                .get(&var.ident)
+                .or_else(|_| RefCell::borrow(&self.current).get(&var.ident)),
+            Resolution::Unresolved => RefCell::borrow(&self.current).get(&var.ident),
+        }
+    }
+
     fn eval_assignment(&mut self, assignment: &Assignment) -> anyhow::Result<Object> {
         let value = self.eval_expression(&assignment.expr)?;
-        let line = &assignment.ident.line;
-        RefCell::borrow_mut(&self.global)
-            .assign(&assignment.ident.lexeme, value.clone())
-            .map_err(|e| anyhow!(runtime_error(line, &e.to_string())))?;
+        let ident = &assignment.ident;
+        let result = match assignment.resolution {
+            Resolution::Local(distance) => {
+                RefCell::borrow_mut(&self.current).assign_at(distance, &ident.lexeme, value.clone())
+            }
+            Resolution::Global if RefCell::borrow(&self.global).local(&ident.lexeme).is_some() => {
+                RefCell::borrow_mut(&self.global).assign(&ident.lexeme, value.clone())
+            }
+            Resolution::Global | Resolution::Unresolved => {
+                RefCell::borrow_mut(&self.current).assign(&ident.lexeme, value.clone())
+            }
+        };
+        result.map_err(|e| anyhow!(runtime_error(&ident.line, &ident.column, &e.to_string())))?;
         Ok(value)
     }
 
-    fn eval_range(&mut self, _range: &Range) -> anyhow::Result<Object> {
-        todo!()
+    fn eval_list(&mut self, list: &ListLiteral) -> anyhow::Result<Object> {
+        let mut items = Vec::new();
+        for item in &list.items {
+            items.push(self.eval_expression(item)?);
+        }
+        Ok(Object::List(Rc::new(RefCell::new(items))))
+    }
+
+    fn eval_comprehension(&mut self, comp: &Comprehension) -> anyhow::Result<Object> {
+        let token = &comp.bracket_token;
+        let items = match self.eval_expression(&comp.iterable)? {
+            Object::List(list) => list.borrow().clone(),
+            Object::Range(start, end) => {
+                let len = (end - start).ceil().max(0.0) as usize;
+                (0..len).map(|i| Object::Number(start + i as f64)).collect()
+            }
+            obj => bail!(runtime_error(
+                &token.line,
+                &token.column,
+                &format!(
+                    "Cannot iterate over a {} in a list comprehension",
+                    obj.type_name()
+                )
+            )),
+        };
+
+        let mut results = Vec::new();
+        for item in items {
+            // every item gets a fresh scope, so closures built from it keep their own
+            let mut env = Environment::new(Some(Rc::clone(&self.current)));
+            env.define(comp.variable.lexeme.clone(), item);
+            let previous = mem::replace(&mut self.current, Rc::new(RefCell::new(env)));
+
+            let result = self.eval_comprehension_item(comp);
+            self.current = previous;
+            if let Some(value) = result? {
+                results.push(value);
+            }
+        }
+        Ok(Object::List(Rc::new(RefCell::new(results))))
+    }
+
+    // the value an item contributes, or none when the condition filters it out
+    fn eval_comprehension_item(&mut self, comp: &Comprehension) -> anyhow::Result<Option<Object>> {
+        if let Some(condition) = &comp.condition {
+            if !self.eval_expression(condition)?.thrutiness() {
+                return Ok(None);
+            }
+        }
+        Ok(Some(self.eval_expression(&comp.expr)?))
+    }
+
+    fn eval_map(&mut self, map: &MapLiteral) -> anyhow::Result<Object> {
+        let mut entries = HashMap::new();
+        for entry in &map.entries {
+            let value = self.eval_expression(&entry.value)?;
+            entries.insert(entry.key.clone(), value);
+        }
+        Ok(Object::Map(Rc::new(RefCell::new(entries))))
+    }
+
+    fn eval_index(&mut self, index: &Index) -> anyhow::Result<Object> {
+        let obj = self.eval_expression(&index.object)?;
+        let position = self.eval_expression(&index.index)?;
+        let line = &index.bracket_token.line;
+        let column = &index.bracket_token.column;
+
+        match obj {
+            Object::List(list) => {
+                let list = list.borrow();
+                let position = position.expect_index(list.len(), line, column)?;
+                Ok(list[position].clone())
+            }
+            // strings index and slice by unicode scalar
+            Object::Str(s) => match position {
+                Object::Range(start, end) => {
+                    let (start, end) = slice_bounds(start, end, str_len(&s), line, column)?;
+                    Ok(Object::Str(
+                        s.chars().skip(start).take(end - start).collect(),
+                    ))
+                }
+                position => {
+                    let position = position.expect_index(str_len(&s), line, column)?;
+                    Ok(Object::Str(s.chars().nth(position).unwrap().to_string()))
+                }
+            },
+            // reading a key that is not in the map yields null
+            Object::Map(map) => {
+                let key = position.expect_string(line, column)?;
+                Ok(map.borrow().get(&key).cloned().unwrap_or(Object::Null))
+            }
+            obj => bail!(runtime_error(
+                line,
+                column,
+                &format!("Cannot index into a {}", obj.type_name())
+            )),
+        }
+    }
+
+    fn eval_set_index(&mut self, set: &SetIndex) -> anyhow::Result<Object> {
+        let obj = self.eval_expression(&set.object)?;
+        let position = self.eval_expression(&set.index)?;
+        let value = self.eval_expression(&set.value)?;
+        let line = &set.bracket_token.line;
+        let column = &set.bracket_token.column;
+
+        match obj {
+            Object::List(list) => {
+                let position = position.expect_index(list.borrow().len(), line, column)?;
+                let value = match &set.operator {
+                    Some(operator) => {
+                        let current = list.borrow()[position].clone();
+                        self.eval_compound(operator, current, value)?
+                    }
+                    None => value,
+                };
+                RefCell::borrow_mut(&list)[position] = value.clone();
+                Ok(value)
+            }
+            Object::Map(map) => {
+                let key = position.expect_string(line, column)?;
+                let value = match &set.operator {
+                    Some(operator) => {
+                        let current = map.borrow().get(&key).cloned().unwrap_or(Object::Null);
+                        self.eval_compound(operator, current, value)?
+                    }
+                    None => value,
+                };
+                RefCell::borrow_mut(&map).insert(key, value.clone());
+                Ok(value)
+            }
+            obj => bail!(runtime_error(
+                line,
+                column,
+                &format!("Cannot assign into an index of a {}", obj.type_name())
+            )),
+        }
+    }
+
+    fn eval_super(&mut self, sup: &Super) -> anyhow::Result<Object> {
+        let keyword = &sup.keyword;
+        let class = match RefCell::borrow(&self.current).get(keyword) {
+            Ok(Object::Callable(c)) => c.class(),
+            _ => None,
+        };
+        let Some(class) = class else {
+            bail!(runtime_error(
+                &keyword.line,
+                &keyword.column,
+                "Can't use 'super' in a class with no superclass"
+            ))
+        };
+
+        let this = Token::new(
+            "this".to_string(),
+            TokenType::This,
+            keyword.line,
+            keyword.column,
+        );
+        let receiver = RefCell::borrow(&self.current).get(&this)?;
+        match class.find_method(&sup.method.lexeme) {
+            Some(method) => Ok(Object::Callable(Box::new(BoundMethod { receiver, method }))),
+            None => bail!(runtime_error(
+                &sup.method.line,
+                &sup.method.column,
+                &format!(
+                    "Superclass '{}' has no method '{}'",
+                    class.ident, sup.method.lexeme
+                )
+            )),
+        }
+    }
+
+    fn eval_object(&mut self, object: &ObjectLiteral) -> anyhow::Result<Object> {
+        let mut instance = Instance::new(Class::anonymous());
+        for field in &object.fields {
+            let value = self.eval_expression(&field.value)?;
+            instance.set(field.ident.clone(), value);
+        }
+        Ok(Object::Instance(Rc::new(RefCell::new(instance))))
+    }
+
+    fn eval_lambda(&mut self, lambda: &FnDecl) -> anyhow::Result<Object> {
+        Ok(Object::Callable(Box::new(Function {
+            declaration: lambda.clone(),
+            closure: Rc::clone(&self.current),
+        })))
+    }
+
+    fn eval_range(&mut self, range: &Range) -> anyhow::Result<Object> {
+        let line = &range.operator.line;
+        let column = &range.operator.column;
+        let start = self
+            .eval_expression(&range.left)?
+            .expect_number(line, column)?;
+        let end = self
+            .eval_expression(&range.right)?
+            .expect_number(line, column)?;
+        Ok(Object::Range(start, end))
     }
 
     fn eval_logical(&mut self, logical: &Logical) -> anyhow::Result<Object> {
@@ -222,25 +1087,57 @@ impl Interpreter {
         Ok(self.eval_expression(&logical.right)?)
     }
 
+    // the arithmetic of `target op= value` on values that were already evaluated
+    fn eval_compound(
+        &self,
+        operator: &Token,
+        current: Object,
+        value: Object,
+    ) -> anyhow::Result<Object> {
+        let result = match operator.ty {
+            TokenType::Plus => current + value,
+            TokenType::Minus => current - value,
+            TokenType::Star => current.mul(value, self.max_result_size),
+            _ => current.div(value, self.int_div),
+        };
+        result.map_err(|e| {
+            anyhow!(runtime_error(
+                &operator.line,
+                &operator.column,
+                &e.to_string()
+            ))
+        })
+    }
+
     fn eval_binary(&mut self, binary: &Binary) -> anyhow::Result<Object> {
         let left = self.eval_expression(&binary.left)?;
         let right = self.eval_expression(&binary.right)?;
         let line = &binary.operator.line;
+        let column = &binary.operator.column;
 
         match binary.operator.ty {
             TokenType::EqualEqual => Ok(Object::Boolean(left == right)),
             TokenType::BangEqual => Ok(Object::Boolean(left != right)),
             TokenType::Minus => {
-                (left - right).map_err(|e| anyhow::anyhow!(runtime_error(line, &e.to_string())))
+                (left - right).map_err(|e| anyhow::anyhow!(runtime_error(line, column, &e.to_string())))
             }
             TokenType::Star => {
-                (left * right).map_err(|e| anyhow::anyhow!(runtime_error(line, &e.to_string())))
+                left.mul(right, self.max_result_size).map_err(|e| anyhow::anyhow!(runtime_error(line, column, &e.to_string())))
             }
             TokenType::Slash => {
-                (left / right).map_err(|e| anyhow::anyhow!(runtime_error(line, &e.to_string())))
+                left.div(right, self.int_div).map_err(|e| anyhow::anyhow!(runtime_error(line, column, &e.to_string())))
+            }
+            TokenType::SlashDot => {
+                left.true_div(right).map_err(|e| anyhow::anyhow!(runtime_error(line, column, &e.to_string())))
+            }
+            TokenType::StarStar => {
+                left.pow(right).map_err(|e| anyhow::anyhow!(runtime_error(line, column, &e.to_string())))
+            }
+            TokenType::Percent => {
+                (left % right).map_err(|e| anyhow::anyhow!(runtime_error(line, column, &e.to_string())))
             }
             TokenType::Plus => {
-                (left + right).map_err(|e| anyhow::anyhow!(runtime_error(line, &e.to_string())))
+                (left + right).map_err(|e| anyhow::anyhow!(runtime_error(line, column, &e.to_string())))
             }
             TokenType::Greater => match left.partial_cmp(&right) {
                 Some(a) => if let Ordering::Greater = a {
@@ -248,7 +1145,7 @@ impl Interpreter {
                 }else {
                     Ok(Object::Boolean(false))
                 },
-                None => bail!(runtime_error(line, "Ordering operators can only be used when both operands are 'string' or 'number'")),
+                None => bail!(runtime_error(line, column, "Ordering operators can only be used when both operands are 'string' or 'number'")),
             },
             TokenType::GreaterEqual => match left.partial_cmp(&right) {
                 Some(a) => if let Ordering::Greater | Ordering::Equal= a {
@@ -256,7 +1153,7 @@ impl Interpreter {
                 }else {
                     Ok(Object::Boolean(false))
                 },
-                None => bail!(runtime_error(line, "Ordering operators can only be used when both operands are 'string' or 'number'")),
+                None => bail!(runtime_error(line, column, "Ordering operators can only be used when both operands are 'string' or 'number'")),
             },
             TokenType::LessEqual => match left.partial_cmp(&right) {
                 Some(a) => if let Ordering::Less | Ordering::Equal= a {
@@ -264,7 +1161,7 @@ impl Interpreter {
                 }else {
                     Ok(Object::Boolean(false))
                 },
-                None => bail!(runtime_error(line, "Ordering operators can only be used when both operands are 'string' or 'number'")),
+                None => bail!(runtime_error(line, column, "Ordering operators can only be used when both operands are 'string' or 'number'")),
             },
             TokenType::Less => match left.partial_cmp(&right) {
                 Some(a) => if let Ordering::Less = a {
@@ -272,20 +1169,23 @@ impl Interpreter {
                 }else {
                     Ok(Object::Boolean(false))
                 },
-                None => bail!(runtime_error(line, "Ordering operators can only be used when both operands are 'string' or 'number'")),
+                None => bail!(runtime_error(line, column, "Ordering operators can only be used when both operands are 'string' or 'number'")),
             },
-            _ => bail!(runtime_error(line, "Unexpected binary operator")),
+            _ => bail!(runtime_error(line, column, "Unexpected binary operator")),
         }
     }
 
     fn eval_unary(&mut self, unary: &Unary) -> anyhow::Result<Object> {
         let value = self.eval_expression(&unary.expr)?;
-        let line = &unary.operator.line;
+        let operand = unary.expr.token().unwrap_or(&unary.operator);
         match unary.operator.ty {
             TokenType::Bang => Ok(Object::Boolean(!value.thrutiness())),
-            TokenType::Minus => Ok(Object::Number(-value.expect_number(line)?)),
+            TokenType::Minus => Ok(Object::Number(
+                -value.expect_number(&operand.line, &operand.column)?,
+            )),
             _ => bail!(runtime_error(
                 &unary.operator.line,
+                &unary.operator.column,
                 "Expected '-' or '!' in unary operations"
             )),
         }
@@ -294,25 +1194,45 @@ impl Interpreter {
     fn eval_call(&mut self, call: &Call) -> anyhow::Result<Object> {
         let callee = self.eval_expression(&call.callee)?;
         let line = &call.paren_token.line;
+        let column = &call.paren_token.column;
 
+        // the callee is evaluated first, then every argument strictly left to
+        // right, so side effects in arguments happen in source order
         let mut args = Vec::new();
         for arg in &call.args {
             args.push(self.eval_expression(&arg)?);
         }
 
         if let Object::Callable(mut c) = callee {
-            if c.arity() != args.len() {
+            if args.len() < c.arity() || args.len() > c.max_arity() {
+                let expected = if c.arity() == c.max_arity() {
+                    c.arity().to_string()
+                } else if c.max_arity() == usize::MAX {
+                    format!("at least {}", c.arity())
+                } else {
+                    format!("{} to {}", c.arity(), c.max_arity())
+                };
                 let msg = &format!(
                     "Expected {} argument(s), but {} were found",
-                    c.arity(),
+                    expected,
                     args.len()
                 );
-                bail!(runtime_error(line, msg))
+                bail!(runtime_error(line, column, msg))
             }
+            self.call_site = (*line, *column);
             return c.call(self, args);
         }
 
-        bail!(runtime_error(line, "Expected callable object"))
+        let token = call.callee.token().unwrap_or(&call.paren_token);
+        let msg = match call.callee.as_ref() {
+            Expression::Var(var) => format!(
+                "Expected callable object, but '{}' is a {}",
+                var.ident.lexeme,
+                callee.type_name()
+            ),
+            _ => format!("Expected callable object, found a {}", callee.type_name()),
+        };
+        bail!(runtime_error(&token.line, &token.column, &msg))
     }
 
     fn eval_literal(&mut self, literal: &Literal) -> anyhow::Result<Object> {
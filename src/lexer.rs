@@ -1,27 +1,38 @@
 use crate::{
-    error::syntax_error,
-    token::{Token, TokenType, KEYWORDS},
+    error::{syntax_error, ErrorReport},
+    token::{Token, TokenType, Trivia, TriviaKind, KEYWORDS},
 };
 use anyhow::bail;
 
 pub struct Lexer {
-    source: String,
+    // indexed by unicode scalar, so positions and columns never split a character
+    source: Vec<char>,
     current: usize,
     start: usize,
     line: usize,
+    line_start: usize,
     tokens: Vec<Token>,
-    errors: String,
+    // errors past `max_errors` are counted but left out of the report
+    errors: ErrorReport,
+    pub max_errors: usize,
+    // whitespace and comments are only recorded when asked for, the parser has no use for them
+    pub capture_trivia: bool,
+    pub trivia: Vec<Trivia>,
 }
 
 impl Lexer {
     pub fn new(source: String) -> Self {
         Self {
-            source,
+            source: source.chars().collect(),
             current: 0,
             start: 0,
             line: 1,
+            line_start: 0,
             tokens: Vec::new(),
-            errors: "".to_string(),
+            errors: ErrorReport::default(),
+            max_errors: 20,
+            capture_trivia: false,
+            trivia: Vec::new(),
         }
     }
 
@@ -29,8 +40,14 @@ impl Lexer {
         self.reset();
         while !self.finished() {
             self.start = self.current;
-            if let Err(e) = self.scan_token() {
-                self.errors.push_str(&e.to_string());
+            let (line, column) = (self.line, self.column());
+            let count = self.tokens.len();
+            match self.scan_token() {
+                Ok(()) if self.capture_trivia && self.tokens.len() == count => {
+                    self.add_trivia(line, column)
+                }
+                Ok(()) => (),
+                Err(e) => self.report(e),
             }
         }
 
@@ -41,11 +58,47 @@ impl Lexer {
         bail!(self.errors.clone())
     }
 
+    fn report(&mut self, e: anyhow::Error) {
+        let (line, col) = (self.line, self.column());
+        self.errors.collect(e, self.max_errors, line, col);
+    }
+
     fn reset(&mut self) {
         self.current = 0;
         self.start = 0;
         self.line = 1;
+        self.line_start = 0;
         self.tokens = Vec::new();
+        self.trivia = Vec::new();
+    }
+
+    // records the source scanned without producing a token, merging runs of whitespace
+    fn add_trivia(&mut self, line: usize, column: usize) {
+        let text = self.lexeme();
+        let before = self.tokens.len();
+        let kind = if text.trim().is_empty() {
+            TriviaKind::Whitespace
+        } else {
+            TriviaKind::Comment
+        };
+
+        if let Some(last) = self.trivia.last_mut() {
+            if kind == TriviaKind::Whitespace
+                && last.kind == TriviaKind::Whitespace
+                && last.before == before
+            {
+                last.text.push_str(&text);
+                return;
+            }
+        }
+
+        self.trivia.push(Trivia {
+            kind,
+            text,
+            line,
+            column,
+            before,
+        });
     }
 
     fn scan_token(&mut self) -> anyhow::Result<()> {
@@ -55,11 +108,51 @@ impl Lexer {
             ')' => self.add_token(TokenType::RightParen),
             '{' => self.add_token(TokenType::LeftBrace),
             '}' => self.add_token(TokenType::RightBrace),
+            '[' => self.add_token(TokenType::LeftBracket),
+            ']' => self.add_token(TokenType::RightBracket),
             ',' => self.add_token(TokenType::Comma),
-            '-' => self.add_token(TokenType::Minus),
-            '+' => self.add_token(TokenType::Plus),
+            ':' => self.add_token(TokenType::Colon),
+            '-' => {
+                if self.complement('=') {
+                    self.add_token(TokenType::MinusEqual);
+                } else {
+                    self.add_token(TokenType::Minus);
+                }
+            }
+            '+' => {
+                if self.complement('=') {
+                    self.add_token(TokenType::PlusEqual);
+                } else {
+                    self.add_token(TokenType::Plus);
+                }
+            }
+            '/' => {
+                // comments are skipped like whitespace, so they may appear between any two tokens
+                if self.complement('/') {
+                    while self.peek() != '\n' && !self.finished() {
+                        self.next_char();
+                    }
+                } else if self.complement('*') {
+                    self.skip_block_comment()?;
+                } else if self.complement('=') {
+                    self.add_token(TokenType::SlashEqual);
+                } else if self.complement('.') {
+                    self.add_token(TokenType::SlashDot);
+                } else {
+                    self.add_token(TokenType::Slash);
+                }
+            }
             ';' => self.add_token(TokenType::Semicolon),
-            '*' => self.add_token(TokenType::Star),
+            '*' => {
+                if self.complement('*') {
+                    self.add_token(TokenType::StarStar);
+                } else if self.complement('=') {
+                    self.add_token(TokenType::StarEqual);
+                } else {
+                    self.add_token(TokenType::Star);
+                }
+            }
+            '%' => self.add_token(TokenType::Percent),
             '!' => {
                 if self.complement('=') {
                     self.add_token(TokenType::BangEqual);
@@ -90,18 +183,36 @@ impl Lexer {
             }
             '.' => {
                 if self.complement('.') {
-                    self.add_token(TokenType::DotDot);
+                    if self.complement('.') {
+                        self.add_token(TokenType::Ellipsis);
+                    } else {
+                        self.add_token(TokenType::DotDot);
+                    }
                 } else {
                     self.add_token(TokenType::Dot);
                 }
             }
+            '|' if self.complement('>') => self.add_token(TokenType::Pipe),
+            // the symbol forms are the same operators as the `and`/`or` keywords
+            '|' if self.complement('|') => self.add_token(TokenType::Or),
+            '&' if self.complement('&') => self.add_token(TokenType::And),
             '"' => self.add_string_token()?,
-            _ if char.is_digit(10) => self.add_number_token(),
+            _ if char.is_digit(10) => self.add_number_token()?,
+            // a leading separator, as in `_5`, rather than a name
+            '_' if self.peek().is_digit(10) => bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                "Misplaced '_' separator before number literal"
+            )),
             _ if char.is_alphabetic() || char == '_' => self.add_identifier_token(),
             ' ' | '\r' | '\t' => (),
-            '\n' => self.line += 1,
+            '\n' => {
+                self.line += 1;
+                self.line_start = self.current;
+            }
             _ => bail!(syntax_error(
                 &self.line,
+                &self.column(),
                 &format!("Unexpected Token '{}'", char)
             )),
         }
@@ -109,13 +220,31 @@ impl Lexer {
         Ok(())
     }
 
+    fn skip_block_comment(&mut self) -> anyhow::Result<()> {
+        let (line, column) = (self.line, self.column());
+        while !(self.peek() == '*' && self.peek1() == '/') {
+            if self.finished() {
+                bail!(syntax_error(&line, &column, "Unterminated block comment"))
+            }
+
+            if self.next_char() == '\n' {
+                self.line += 1;
+                self.line_start = self.current;
+            }
+        }
+
+        // consumes the closing '*/'
+        self.current += 2;
+        Ok(())
+    }
+
     fn add_identifier_token(&mut self) {
         while self.peek().is_alphanumeric() || self.peek() == '_' {
             self.next_char();
         }
 
-        let something = &self.source[self.start..self.current];
-        if let Some(kw) = KEYWORDS.get(something) {
+        let something = self.lexeme();
+        if let Some(kw) = KEYWORDS.get(&something) {
             self.add_token(kw.clone());
             return;
         }
@@ -124,38 +253,205 @@ impl Lexer {
     }
 
     fn add_string_token(&mut self) -> anyhow::Result<()> {
+        let mut string = String::new();
+        let mut escape_error = None;
         while self.peek() != '"' && !self.finished() {
-            self.next_char();
+            let char = self.next_char();
+            if char == '\\' && !self.finished() {
+                match self.scan_escape() {
+                    Ok(c) => string.push(c),
+                    Err(e) => escape_error = escape_error.or(Some(e)),
+                }
+            } else {
+                string.push(char);
+            }
         }
 
         if self.finished() {
-            bail!(syntax_error(&self.line, "Unterminated string"))
+            bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                "Unterminated string"
+            ))
         }
 
         //consumes the '"'
         self.next_char();
 
-        let string = &self.source[self.start + 1..self.current - 1];
-        self.add_token(TokenType::String(String::from(string)));
+        if let Some(e) = escape_error {
+            return Err(e);
+        }
+
+        self.add_token(TokenType::String(string));
         Ok(())
     }
 
-    fn add_number_token(&mut self) {
-        while self.peek().is_digit(10) {
-            self.next_char();
+    fn scan_escape(&mut self) -> anyhow::Result<char> {
+        let char = self.next_char();
+        match char {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '\\' => Ok('\\'),
+            '"' => Ok('"'),
+            '0' => Ok('\0'),
+            'u' => self.scan_unicode_escape(),
+            _ => bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                &format!("Unknown escape sequence '\\{}'", char)
+            )),
+        }
+    }
+
+    fn scan_unicode_escape(&mut self) -> anyhow::Result<char> {
+        if !self.complement('{') {
+            bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                "Expected '{' after '\\u' in unicode escape"
+            ))
+        }
+
+        let mut digits = String::new();
+        while self.peek().is_ascii_hexdigit() {
+            digits.push(self.next_char());
         }
 
-        if self.peek() == '.' && self.peek1().is_digit(10) {
+        if !self.complement('}') {
+            bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                "Expected '}' at the end of unicode escape"
+            ))
+        }
+
+        if digits.is_empty() || digits.len() > 6 {
+            bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                "Expected 1 to 6 hex digits in unicode escape"
+            ))
+        }
+
+        let code = u32::from_str_radix(&digits, 16).unwrap();
+        match char::from_u32(code) {
+            Some(c) => Ok(c),
+            None => bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                &format!("Invalid unicode code point '{:X}'", code)
+            )),
+        }
+    }
+
+    fn add_number_token(&mut self) -> anyhow::Result<()> {
+        if self.source[self.start..self.current] == ['0'] {
+            if self.complement('x') {
+                return self.add_radix_number_token(16);
+            }
+            if self.complement('b') {
+                return self.add_radix_number_token(2);
+            }
+        }
+
+        self.scan_digits();
+
+        if self.peek() == '.' && (self.peek1().is_digit(10) || self.peek1() == '_') {
             self.next_char();
+            self.scan_digits();
+        }
+
+        if self.complement('e') || self.complement('E') {
+            if !self.complement('+') {
+                self.complement('-');
+            }
+
+            if !self.peek().is_digit(10) {
+                bail!(syntax_error(
+                    &self.line,
+                    &self.column(),
+                    "Expected digits in the exponent of number literal"
+                ))
+            }
+
             while self.peek().is_digit(10) {
                 self.next_char();
             }
         }
 
-        let number = self.source[self.start..self.current]
-            .parse::<f64>()
-            .unwrap();
+        // a separator must sit between two digits, ruling out `1__0`, `1_` or `1_e5`
+        let literal = self.lexeme();
+        let digits = literal.chars().collect::<Vec<_>>();
+        let misplaced = digits.iter().enumerate().any(|(i, c)| {
+            let between_digits = i > 0
+                && digits[i - 1].is_digit(10)
+                && digits.get(i + 1).is_some_and(|c| c.is_digit(10));
+            *c == '_' && !between_digits
+        });
+        if misplaced {
+            bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                &format!("Misplaced '_' separator in number literal '{}'", literal)
+            ))
+        }
+
+        let number = literal.replace('_', "").parse::<f64>().unwrap();
         self.add_token(TokenType::Number(number));
+        Ok(())
+    }
+
+    fn scan_digits(&mut self) {
+        while self.peek().is_digit(10) || self.peek() == '_' {
+            self.next_char();
+        }
+    }
+
+    fn add_radix_number_token(&mut self, radix: u32) -> anyhow::Result<()> {
+        while self.peek().is_digit(radix) {
+            self.next_char();
+        }
+
+        // a digit out of range, as in `0b102`, would otherwise start a second token
+        if self.peek().is_alphanumeric() {
+            let invalid = self.peek();
+            while self.peek().is_alphanumeric() {
+                self.next_char();
+            }
+            bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                &format!(
+                    "Invalid digit '{}' in number literal '{}'",
+                    invalid,
+                    self.lexeme()
+                )
+            ))
+        }
+
+        let digits = self.source[self.start + 2..self.current]
+            .iter()
+            .collect::<String>();
+        if digits.is_empty() {
+            bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                &format!("Expected digits after '{}'", self.lexeme())
+            ))
+        }
+
+        match i64::from_str_radix(&digits, radix) {
+            Ok(n) => {
+                self.add_token(TokenType::Number(n as f64));
+                Ok(())
+            }
+            Err(_) => bail!(syntax_error(
+                &self.line,
+                &self.column(),
+                &format!("Number literal '{}' is too large", self.lexeme())
+            )),
+        }
     }
 
     fn complement(&mut self, c: char) -> bool {
@@ -168,27 +464,35 @@ impl Lexer {
     }
 
     fn add_token(&mut self, ty: TokenType) {
-        let lexeme = self.source[self.start..self.current].to_string();
-        let token = Token::new(lexeme, ty, self.line);
+        let lexeme = self.lexeme();
+        let token = Token::new(lexeme, ty, self.line, self.column());
         self.tokens.push(token);
     }
 
+    fn lexeme(&self) -> String {
+        self.source[self.start..self.current].iter().collect()
+    }
+
+    fn column(&self) -> usize {
+        self.start - self.line_start + 1
+    }
+
     fn peek(&self) -> char {
         if self.finished() {
             return '\0';
         }
-        self.source.chars().nth(self.current).unwrap()
+        self.source[self.current]
     }
 
     fn peek1(&self) -> char {
-        if self.current + 1 == self.source.len() {
+        if self.current + 1 >= self.source.len() {
             return '\0';
         }
-        self.source.chars().nth(self.current + 1).unwrap()
+        self.source[self.current + 1]
     }
 
     fn next_char(&mut self) -> char {
-        let current_char = self.source.chars().nth(self.current).unwrap();
+        let current_char = self.source[self.current];
         self.current += 1;
         current_char
     }
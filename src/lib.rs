@@ -4,6 +4,9 @@ pub mod grammar;
 pub mod interpreter;
 pub mod lexer;
 pub mod parser;
+pub mod printer;
+pub mod resolver;
 pub mod runtime;
+pub mod stats;
 pub mod std;
 pub mod token;
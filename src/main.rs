@@ -1,21 +1,122 @@
-use interp::{interpreter::Interpreter, lexer::Lexer, parser::Parser};
-use std::fs::read_to_string;
+use interp::{
+    interpreter::Interpreter, lexer::Lexer, parser::Parser, printer::pretty_print,
+    resolver::Resolver, runtime::Object, stats::ParseStats,
+};
+use std::{
+    cell::RefCell,
+    fs::read_to_string,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+// exit codes following sysexits: malformed source, and an error while running it
+const EX_DATAERR: i32 = 65;
+const EX_SOFTWARE: i32 = 70;
 
 fn main() -> anyhow::Result<()> {
-    let source = read_to_string("test.lox").unwrap();
-    let mut lexer = Lexer::new(source.trim().to_string());
+    let max_errors =
+        std::env::args().find_map(|arg| arg.strip_prefix("--max-errors=")?.parse().ok());
     let mut interp = Interpreter::new();
-    match lexer.tokenize() {
-        Ok(tokens) => {
-            let mut parser = Parser::new(tokens);
-            let declarations = parser.parse()?;
-            interp.interpret(declarations)?
+    interp.trace = std::env::args().any(|arg| arg == "--trace");
+    interp.debug = std::env::args().any(|arg| arg == "--debug");
+    interp.lenient = std::env::args().any(|arg| arg == "--lenient");
+    interp.sandbox = std::env::args().any(|arg| arg == "--sandbox");
+    interp.keep_going = std::env::args().any(|arg| arg == "--keep-going");
+    interp.strict_float = std::env::args().any(|arg| arg == "--strict-float");
+    interp.int_div = std::env::args().any(|arg| arg == "--int-div");
+
+    // the first argument that isn't a flag is the script to run
+    match std::env::args().skip(1).find(|arg| !arg.starts_with("--")) {
+        Some(path) => run_file(&mut interp, Path::new(&path), max_errors),
+        None => repl(&mut interp),
+    }
+}
+
+// `-` in place of a path reads the whole program from stdin
+fn run_file(
+    interp: &mut Interpreter,
+    path: &Path,
+    max_errors: Option<usize>,
+) -> anyhow::Result<()> {
+    let source = if path == Path::new("-") {
+        io::read_to_string(io::stdin())?
+    } else {
+        interp.files.push(path.canonicalize()?);
+        read_to_string(path)?
+    };
+    let mut lexer = Lexer::new(source.trim().to_string());
+    let max_errors = max_errors.unwrap_or(lexer.max_errors);
+    lexer.max_errors = max_errors;
+    let tokens = lexer
+        .tokenize()
+        .unwrap_or_else(|e| exit_with(e, EX_DATAERR));
+    let token_count = tokens.len();
+    let mut parser = Parser::new(tokens);
+    parser.max_errors = max_errors;
+    let mut declarations = parser.parse().unwrap_or_else(|e| exit_with(e, EX_DATAERR));
+    // reports what the file parsed into without resolving or executing it
+    if std::env::args().any(|arg| arg == "--no-run") {
+        println!("{}", ParseStats::collect(token_count, &declarations));
+        return Ok(());
+    }
+    // prints the syntax tree instead of resolving and running it
+    if std::env::args().any(|arg| arg == "--ast") {
+        println!("{}", pretty_print(&declarations));
+        return Ok(());
+    }
+    if let Err(e) = Resolver::new().resolve(&mut declarations) {
+        exit_with(e, EX_DATAERR)
+    }
+    if let Err(e) = interp.interpret(declarations) {
+        exit_with(e, EX_SOFTWARE)
+    }
+
+    Ok(())
+}
+
+fn exit_with(e: anyhow::Error, code: i32) -> ! {
+    println!("{e}");
+    std::process::exit(code)
+}
+
+// reads one line at a time, keeping the same interpreter so bindings persist between
+// lines, and names those each line defines. Errors are reported without leaving the loop
+fn repl(interp: &mut Interpreter) -> anyhow::Result<()> {
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+
+        // a lone expression such as "1 + 2" needs no trailing ';'
+        let mut source = line?.trim().to_string();
+        if source.is_empty() {
+            continue;
         }
-        Err(e) => {
-            println!("{e}");
-            std::process::exit(65);
+        if !source.ends_with(';') && !source.ends_with('}') {
+            source.push(';');
+        }
+
+        let snapshot = RefCell::borrow(&interp.current).snapshot();
+        match eval_line(interp, source) {
+            Ok(Object::Null) => (),
+            Ok(value) => println!("{}", interp.show(&value)),
+            Err(e) => println!("{}", e.to_string().trim()),
+        }
+
+        let defined = RefCell::borrow(&interp.current).diff(&snapshot);
+        if !defined.is_empty() {
+            println!("defined: {}", defined.join(", "));
         }
     }
+}
 
-    Ok(())
+fn eval_line(interp: &mut Interpreter, source: String) -> anyhow::Result<Object> {
+    let tokens = Lexer::new(source).tokenize()?;
+    let mut declarations = Parser::new(tokens).parse()?;
+    Resolver::new().resolve(&mut declarations)?;
+    interp.interpret_value(declarations)
 }
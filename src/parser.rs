@@ -1,11 +1,15 @@
+use std::mem;
+
 use anyhow::bail;
 
 use crate::{
-    error::syntax_error,
+    error::{syntax_error, ErrorReport},
     grammar::{
-        Assignment, Binary, BlockStmt, Call, ClassDecl, Declaration, ExprStmt, Expression, FnDecl,
-        Get, IfStmt, LetDecl, Literal, Logical, Range, ReturnStmt, Set, Statement, StmtDecl, Unary,
-        WhileStmt,
+        Assignment, Binary, BlockStmt, BreakStmt, Call, ClassDecl, Comprehension, ContinueStmt,
+        Declaration, ExportDecl, ExprStmt, Expression, FnDecl, Get, IfStmt, ImportStmt, Index,
+        LetDecl, ListLiteral, Literal, Logical, MapEntry, MapLiteral, ObjectField, ObjectLiteral,
+        Range, Resolution, ReturnStmt, Set, SetIndex, Statement, StmtDecl, Super, TryStmt, Unary,
+        Variable, WhileStmt,
     },
     token::{Token, TokenType},
 };
@@ -13,7 +17,16 @@ use crate::{
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
-    errors: String,
+    // errors past `max_errors` are counted but left out of the report
+    errors: ErrorReport,
+    pub max_errors: usize,
+    // number of loops enclosing the statement being parsed, reset inside function bodies
+    loop_depth: usize,
+    // nesting of expressions and blocks, bounded by `max_depth` so deep input can't overflow the stack.
+    // A level of parentheses takes about 40KB of stack in a debug build, so the default
+    // keeps parsing, resolving and running the deepest program accepted within a 2MB thread
+    depth: usize,
+    pub max_depth: usize,
 }
 
 impl Parser {
@@ -21,17 +34,27 @@ impl Parser {
         Self {
             tokens,
             current: 0,
-            errors: "".to_string(),
+            errors: ErrorReport::default(),
+            max_errors: 20,
+            loop_depth: 0,
+            depth: 0,
+            max_depth: 32,
         }
     }
 
     pub fn parse(&mut self) -> anyhow::Result<Vec<Declaration>> {
         let mut declarations = Vec::new();
         while !self.finished() {
-            match self.parse_declaration() {
+            // exports are only meaningful at the top level of a file
+            let declaration = match self.peek().ty {
+                TokenType::Export => self.parse_export_declaration(),
+                _ => self.parse_declaration(),
+            };
+
+            match declaration {
                 Ok(s) => declarations.push(s),
                 Err(e) => {
-                    self.errors.push_str(&e.to_string());
+                    self.report(e);
                     self.synchronize()
                 }
             }
@@ -44,43 +67,108 @@ impl Parser {
         bail!(self.errors.clone())
     }
 
+    fn report(&mut self, e: anyhow::Error) {
+        let (line, col) = (self.peek().line, self.peek().column);
+        self.errors.collect(e, self.max_errors, line, col);
+    }
+
     fn parse_declaration(&mut self) -> anyhow::Result<Declaration> {
-        if let TokenType::Let = self.peek().ty {
+        if let TokenType::Let | TokenType::Const = self.peek().ty {
             return self.parse_let_declaration();
         }
 
+        // `fn(` starts a function expression rather than a declaration
         if let TokenType::Fn = self.peek().ty {
-            return self.parse_fn_statement();
+            if !matches!(self.peek_next().map(|t| &t.ty), Some(TokenType::LeftParen)) {
+                return self.parse_fn_declaration();
+            }
         }
 
         if let TokenType::Class = self.peek().ty {
-            return self.parse_class_statement();
+            return self.parse_class_declaration();
         }
 
         let stmt = self.parse_statment()?;
         Ok(Declaration::StmtDecl(StmtDecl::new(stmt)))
     }
 
-    fn parse_class_statement(&mut self) -> anyhow::Result<Declaration> {
+    fn parse_export_declaration(&mut self) -> anyhow::Result<Declaration> {
+        let export_token = self.next_token().clone();
+
+        if !matches!(
+            self.peek().ty,
+            TokenType::Let | TokenType::Const | TokenType::Fn | TokenType::Class
+        ) {
+            bail!(syntax_error(
+                &export_token.line,
+                &export_token.column,
+                &format!(
+                    "Expected 'let', 'const', 'fn' or 'class' after 'export', found {}",
+                    self.found()
+                )
+            ))
+        }
+
+        let decl = self.parse_declaration()?;
+        Ok(Declaration::ExportDecl(ExportDecl::new(
+            export_token,
+            Box::new(decl),
+        )))
+    }
+
+    fn parse_class_declaration(&mut self) -> anyhow::Result<Declaration> {
         let class_token = self.next_token().clone();
         let line = class_token.line;
+        let column = class_token.column;
         let ident = self
-            .expect(TokenType::Identifier, "Expect class identifier", line)?
+            .expect(
+                TokenType::Identifier,
+                "Expect class identifier",
+                line,
+                column,
+            )?
             .clone();
 
+        let mut superclass = None;
+        if let TokenType::Less = self.peek().ty {
+            self.next_token();
+            let parent = self
+                .expect(
+                    TokenType::Identifier,
+                    "Expect superclass identifier after '<'",
+                    line,
+                    column,
+                )?
+                .clone();
+
+            if parent.lexeme == ident.lexeme {
+                bail!(syntax_error(
+                    &parent.line,
+                    &parent.column,
+                    "A class can't inherit from itself"
+                ))
+            }
+            superclass = Some(parent);
+        }
+
         self.expect(
             TokenType::LeftBrace,
             "Expect '{' at beggining of class body",
             line,
+            column,
         )?;
 
         let mut methods = Vec::new();
         while !matches!(self.peek().ty, TokenType::RightBrace) && !self.finished() {
-            let fun = self.parse_fn_statement()?;
+            let fun = self.parse_fn_declaration()?;
             if let Declaration::FnDecl(decl) = fun {
                 methods.push(decl);
             } else {
-                bail!(syntax_error(&line, "Expect only methods in class body"))
+                bail!(syntax_error(
+                    &line,
+                    &column,
+                    "Expect only methods in class body"
+                ))
             }
         }
 
@@ -88,20 +176,26 @@ impl Parser {
             TokenType::RightBrace,
             "Expect '}' at end of class declaration",
             line,
+            column,
         )?;
 
-        Ok(Declaration::ClassDecl(ClassDecl::new(ident, methods)))
+        Ok(Declaration::ClassDecl(ClassDecl::new(
+            ident, superclass, methods,
+        )))
     }
 
     fn parse_let_declaration(&mut self) -> anyhow::Result<Declaration> {
         let let_token = self.next_token();
         let line = let_token.line;
+        let column = let_token.column;
+        let constant = let_token.ty == TokenType::Const;
 
         let ident = self
             .expect(
                 TokenType::Identifier,
                 "expected identifier after let declaration",
                 line,
+                column,
             )?
             .clone();
 
@@ -109,18 +203,29 @@ impl Parser {
         if let TokenType::Equal = self.peek().ty {
             self.next_token();
             init = Some(self.parse_expression()?);
+        } else if constant {
+            // a constant could never be given a value later
+            bail!(syntax_error(
+                &line,
+                &column,
+                &format!(
+                    "Expected '=' after const declaration, found {}",
+                    self.found()
+                )
+            ))
         }
 
         self.expect(
             TokenType::Semicolon,
             "Expect ';' after variable declaration",
             line,
+            column,
         )?;
 
-        Ok(Declaration::LetDecl(LetDecl::new(ident, init)))
+        Ok(Declaration::LetDecl(LetDecl::new(ident, init, constant)))
     }
 
-    fn parse_fn_statement(&mut self) -> anyhow::Result<Declaration> {
+    fn parse_fn_declaration(&mut self) -> anyhow::Result<Declaration> {
         let fn_token = self.next_token().clone();
 
         let ident = self
@@ -128,12 +233,22 @@ impl Parser {
                 TokenType::Identifier,
                 "Expected function identifier",
                 fn_token.line,
+                fn_token.column,
             )?
             .clone();
 
-        let params = self.parse_fn_params()?;
-        let body = self.parse_block_statement()?;
-        Ok(Declaration::FnDecl(FnDecl::new(ident, params, body)))
+        let (params, variadic) = self.parse_fn_params()?;
+        let body = self.parse_fn_body()?;
+        Ok(Declaration::FnDecl(FnDecl::new(
+            ident, params, variadic, body,
+        )))
+    }
+
+    fn parse_fn_body(&mut self) -> anyhow::Result<Statement> {
+        let loop_depth = mem::take(&mut self.loop_depth);
+        let body = self.parse_block_statement();
+        self.loop_depth = loop_depth;
+        body
     }
 
     fn parse_statment(&mut self) -> anyhow::Result<Statement> {
@@ -157,11 +272,24 @@ impl Parser {
             return self.parse_return_statement();
         }
 
+        if let TokenType::Import = self.peek().ty {
+            return self.parse_import_statement();
+        }
+
+        if let TokenType::Try = self.peek().ty {
+            return self.parse_try_statement();
+        }
+
+        if let TokenType::Break | TokenType::Continue = self.peek().ty {
+            return self.parse_loop_control_statement();
+        }
+
         let expr = self.parse_expression()?;
         self.expect(
             TokenType::Semicolon,
             "Expected ';' after expression",
             self.peek_previous().line,
+            self.peek_previous().column,
         )?;
         Ok(Statement::ExprStmt(ExprStmt::new(expr)))
     }
@@ -178,102 +306,220 @@ impl Parser {
             TokenType::Semicolon,
             "Expected ';' after return statement",
             return_token.line,
+            return_token.column,
         )?;
 
         Ok(Statement::ReturnStmt(ReturnStmt::new(return_token, expr)))
     }
 
+    fn parse_loop_control_statement(&mut self) -> anyhow::Result<Statement> {
+        let token = self.next_token().clone();
+
+        if self.loop_depth == 0 {
+            bail!(syntax_error(
+                &token.line,
+                &token.column,
+                &format!("'{}' outside of a loop", token.lexeme)
+            ))
+        }
+
+        self.expect(
+            TokenType::Semicolon,
+            &format!("Expected ';' after '{}'", token.lexeme),
+            token.line,
+            token.column,
+        )?;
+
+        Ok(match token.ty {
+            TokenType::Break => Statement::BreakStmt(BreakStmt::new(token)),
+            _ => Statement::ContinueStmt(ContinueStmt::new(token)),
+        })
+    }
+
+    fn parse_import_statement(&mut self) -> anyhow::Result<Statement> {
+        let import_token = self.next_token().clone();
+        let line = import_token.line;
+        let column = import_token.column;
+
+        let path = match self.next_token().ty.clone() {
+            TokenType::String(path) => path,
+            _ => bail!(syntax_error(
+                &line,
+                &column,
+                "Expected file path string after 'import'"
+            )),
+        };
+
+        let mut alias = None;
+        if let TokenType::As = self.peek().ty {
+            self.next_token();
+            alias = Some(
+                self.expect(
+                    TokenType::Identifier,
+                    "Expected module alias after 'as'",
+                    line,
+                    column,
+                )?
+                .clone(),
+            );
+        }
+
+        self.expect(
+            TokenType::Semicolon,
+            "Expected ';' after import statement",
+            line,
+            column,
+        )?;
+
+        Ok(Statement::ImportStmt(ImportStmt::new(
+            import_token,
+            path,
+            alias,
+        )))
+    }
+
     fn parse_for_statement(&mut self) -> anyhow::Result<Statement> {
         let for_token = self.next_token();
         let line = for_token.line;
+        let column = for_token.column;
 
-        let variable = self
+        let mut variable = self
             .expect(
                 TokenType::Identifier,
                 "Expected identifier after 'for' keyword",
                 line,
+                column,
             )?
             .clone();
+
+        // `for _ in` still needs a counter, so bind it to a name that cannot be written
+        if variable.lexeme == "_" {
+            variable.lexeme = format!("_@{}:{}", variable.line, variable.column);
+        }
         self.expect(
             TokenType::In,
             "Expected 'in' keyword after identifier in for loop declaration",
             line,
+            column,
         )?;
 
         let range = self.parse_range()?;
-        let body = self.parse_block_statement()?;
 
-        // Extract start and end values from the range expression.
+        // Extract the start and end expressions, both evaluated at runtime.
         let (start, end) = match range {
-            Expression::Range(r) => match (*r.left, *r.right) {
-                (
-                    Expression::Literal(Literal::Number(start)),
-                    Expression::Literal(Literal::Number(end)),
-                ) => (start, end),
-                _ => bail!(syntax_error(
-                    &line,
-                    "Expected range operands to evaluate to a number"
-                )),
-            },
+            Expression::Range(r) => (*r.left, *r.right),
             _ => bail!(syntax_error(
                 &line,
+                &column,
                 "Expected range expression (a..b) in for loop declaration"
             )),
         };
 
+        // checked up front so a missing brace is reported where it was expected
+        if self.peek().ty != TokenType::LeftBrace || self.finished() {
+            bail!(syntax_error(
+                &self.peek().line,
+                &self.peek().column,
+                &format!("Expected '{{' after for loop range, found {}", self.found())
+            ))
+        }
+        let body = self.parse_loop_body()?;
+
         // Create the loop variable declaration: let variable = start;
-        let var_decl = Declaration::LetDecl(LetDecl::new(
-            variable.clone(),
-            Some(Expression::Literal(Literal::Number(start))),
-        ));
+        let var_decl = Declaration::LetDecl(LetDecl::new(variable.clone(), Some(start), false));
+
+        // The end bound is evaluated once, into a binding that cannot be written.
+        let bound = Token::new(
+            format!("_end@{}:{}", line, column),
+            TokenType::Identifier,
+            line,
+            column,
+        );
+        let bound_decl = Declaration::LetDecl(LetDecl::new(bound.clone(), Some(end), false));
 
         // Build the loop condition: variable < end.
         let condition = Expression::Binary(Binary::new(
-            Box::new(Expression::Var(variable.clone())),
-            Token::new("<".to_string(), TokenType::Less, line),
-            Box::new(Expression::Literal(Literal::Number(end))),
+            Box::new(var(variable.clone())),
+            Token::new("<".to_string(), TokenType::Less, line, column),
+            Box::new(var(bound)),
         ));
 
         // Build the increment statement: variable = variable + 1.
         let increment_expr = Expression::Binary(Binary::new(
-            Box::new(Expression::Var(variable.clone())),
-            Token::new("+".to_string(), TokenType::Plus, line),
+            Box::new(var(variable.clone())),
+            Token::new("+".to_string(), TokenType::Plus, line, column),
             Box::new(Expression::Literal(Literal::Number(1.0))),
         ));
-        let assign =
-            Expression::Assignment(Assignment::new(variable.clone(), Box::new(increment_expr)));
-        let iteration = Statement::ExprStmt(ExprStmt::new(assign));
-
-        // Ensure the loop body is a block and append the iteration statement.
-        let while_body = match body {
-            Statement::BlockStmt(mut block) => {
-                block
-                    .stmts
-                    .push(Declaration::StmtDecl(StmtDecl::new(iteration)));
-                Statement::BlockStmt(block)
-            }
-            _ => bail!(syntax_error(
-                &line,
-                "Expected block after for loop declaration"
-            )),
-        };
+        let assign = Expression::Assignment(Assignment::new(
+            variable.clone(),
+            Box::new(increment_expr),
+            Resolution::Unresolved,
+        ));
 
-        // Construct the while loop using the condition and modified body.
+        // Construct the while loop, running the increment after every iteration.
         let while_stmt = Declaration::StmtDecl(StmtDecl::new(Statement::WhileStmt(
-            WhileStmt::new(condition, Box::new(while_body)),
+            WhileStmt::new(condition, Box::new(body), Some(assign)),
         )));
 
         // Return the desugared for-loop as a block containing the variable declaration and while loop.
         Ok(Statement::BlockStmt(BlockStmt::new(vec![
-            var_decl, while_stmt,
+            var_decl, bound_decl, while_stmt,
         ])))
     }
 
+    fn parse_try_statement(&mut self) -> anyhow::Result<Statement> {
+        let try_token = self.next_token().clone();
+        let body = Box::new(self.parse_block_statement()?);
+
+        let mut catch_ident = None;
+        let mut catch_body = None;
+        if let TokenType::Catch = self.peek().ty {
+            self.next_token();
+            // the name the error message is bound to can be left out
+            if let TokenType::Identifier = self.peek().ty {
+                catch_ident = Some(self.next_token().clone());
+            }
+            catch_body = Some(Box::new(self.parse_block_statement()?));
+        }
+
+        let mut finally_body = None;
+        if let TokenType::Finally = self.peek().ty {
+            self.next_token();
+            finally_body = Some(Box::new(self.parse_block_statement()?));
+        }
+
+        if catch_body.is_none() && finally_body.is_none() {
+            bail!(syntax_error(
+                &try_token.line,
+                &try_token.column,
+                &format!(
+                    "Expected 'catch' or 'finally' after try block, found {}",
+                    self.found()
+                )
+            ))
+        }
+
+        Ok(Statement::TryStmt(TryStmt::new(
+            try_token,
+            body,
+            catch_ident,
+            catch_body,
+            finally_body,
+        )))
+    }
+
     fn parse_while_statement(&mut self) -> anyhow::Result<Statement> {
         let _while_token = self.next_token();
         let condition = self.parse_expression()?;
-        let body = Box::new(self.parse_block_statement()?);
-        Ok(Statement::WhileStmt(WhileStmt::new(condition, body)))
+        let body = Box::new(self.parse_loop_body()?);
+        Ok(Statement::WhileStmt(WhileStmt::new(condition, body, None)))
+    }
+
+    fn parse_loop_body(&mut self) -> anyhow::Result<Statement> {
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+        body
     }
 
     fn parse_if_statement(&mut self) -> anyhow::Result<Statement> {
@@ -299,48 +545,73 @@ impl Parser {
         let left_brace_token = self.expect(
             TokenType::LeftBrace,
             "Expected '{' at begining of block",
-            self.peek_previous().line,
+            self.peek().line,
+            self.peek().column,
         )?;
         let line = left_brace_token.line;
+        let column = left_brace_token.column;
 
         let mut stmts = Vec::new();
 
         while self.current < self.tokens.len()
             && !matches!(self.tokens[self.current].ty, TokenType::RightBrace)
         {
-            stmts.push(self.parse_declaration()?);
+            stmts.push(self.nested(Self::parse_declaration)?);
         }
 
         if self.current >= self.tokens.len() {
-            return Err(anyhow::anyhow!(syntax_error(&line, "Unclosed block")));
+            return Err(anyhow::anyhow!(syntax_error(
+                &line,
+                &column,
+                "Unclosed block"
+            )));
         }
 
         self.expect(
             TokenType::RightBrace,
             "Expected '}' at the end of scope",
             line,
+            column,
         )?;
 
         Ok(Statement::BlockStmt(BlockStmt::new(stmts)))
     }
 
     fn parse_expression(&mut self) -> anyhow::Result<Expression> {
-        self.parse_assignment()
+        self.nested(Self::parse_assignment)
+    }
+
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        if self.depth >= self.max_depth {
+            bail!(syntax_error(
+                &self.peek().line,
+                &self.peek().column,
+                "Expression too deeply nested"
+            ))
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
     }
 
     fn parse_assignment(&mut self) -> anyhow::Result<Expression> {
-        let expr = self.parse_range()?;
+        let expr = self.parse_pipe()?;
 
         if let TokenType::Equal = self.peek().ty {
             //consumens the '=' token
             let equals = self.next_token().clone();
-            let value = self.parse_assignment()?;
+            let value = self.nested(Self::parse_assignment)?;
 
             if let Expression::Var(v) = expr {
-                let ident = v;
                 return Ok(Expression::Assignment(Assignment::new(
-                    ident,
+                    v.ident,
                     Box::new(value),
+                    Resolution::Unresolved,
                 )));
             } else if let Expression::Get(g) = expr {
                 return Ok(Expression::Set(Set::new(
@@ -348,35 +619,100 @@ impl Parser {
                     g.field,
                     Box::new(value),
                 )));
+            } else if let Expression::Index(i) = expr {
+                return Ok(Expression::SetIndex(SetIndex::new(
+                    i.object,
+                    i.bracket_token,
+                    i.index,
+                    Box::new(value),
+                    None,
+                )));
             }
 
-            bail!(syntax_error(&equals.line, "Invalid assigment target"))
+            bail!(syntax_error(
+                &equals.line,
+                &equals.column,
+                &format!(
+                    "Invalid assignment target, cannot assign to {}",
+                    describe_target(&expr)
+                )
+            ))
+        }
+
+        if let TokenType::PlusEqual
+        | TokenType::MinusEqual
+        | TokenType::StarEqual
+        | TokenType::SlashEqual = self.peek().ty
+        {
+            let op = self.next_token().clone();
+            let value = self.nested(Self::parse_assignment)?;
+
+            let (ty, lexeme) = match op.ty {
+                TokenType::PlusEqual => (TokenType::Plus, "+"),
+                TokenType::MinusEqual => (TokenType::Minus, "-"),
+                TokenType::StarEqual => (TokenType::Star, "*"),
+                _ => (TokenType::Slash, "/"),
+            };
+            let operator = Token::new(lexeme.to_string(), ty, op.line, op.column);
+
+            // desugars `x op= e` into `x = x op e`
+            if let Expression::Var(v) = expr {
+                let binary = Expression::Binary(Binary::new(
+                    Box::new(var(v.ident.clone())),
+                    operator,
+                    Box::new(value),
+                ));
+                return Ok(Expression::Assignment(Assignment::new(
+                    v.ident,
+                    Box::new(binary),
+                    Resolution::Unresolved,
+                )));
+            }
+
+            // an element is read and written in place, so its object and index are
+            // evaluated once rather than desugared into a second, repeated, lookup
+            if let Expression::Index(i) = expr {
+                return Ok(Expression::SetIndex(SetIndex::new(
+                    i.object,
+                    i.bracket_token,
+                    i.index,
+                    Box::new(value),
+                    Some(operator),
+                )));
+            }
+
+            bail!(syntax_error(
+                &op.line,
+                &op.column,
+                "Invalid assignment target"
+            ))
         }
 
         Ok(expr)
     }
 
-    fn parse_range(&mut self) -> anyhow::Result<Expression> {
-        let left = self.parse_or()?;
+    fn parse_pipe(&mut self) -> anyhow::Result<Expression> {
+        let mut expr = self.parse_or()?;
 
-        if let TokenType::DotDot = self.peek().ty {
-            self.next_token();
-            let right = self.parse_or()?;
-            return Ok(Expression::Range(Range::new(
-                Box::new(left),
-                Box::new(right),
-            )));
+        // desugars `x |> f` into `f(x)`
+        while let TokenType::Pipe = self.peek().ty {
+            let pipe = self.next_token().clone();
+            let callee = self.parse_or()?;
+            expr = Expression::Call(Call::new(Box::new(callee), pipe, vec![expr]));
         }
 
-        Ok(left)
+        Ok(expr)
     }
 
+    // `||` and `&&` lex to the same tokens as `or` and `and`, so the two spellings share a
+    // precedence level and mix freely: `a and b || c` is `(a and b) || c`, and
+    // `a or b && c` is `a or (b && c)`
     fn parse_or(&mut self) -> anyhow::Result<Expression> {
         let mut left = self.parse_and()?;
 
         while let TokenType::Or = self.peek().ty {
             let op = self.next_token().clone();
-            let right = self.parse_or()?;
+            let right = self.parse_and()?;
             left = Expression::Logical(Logical::new(Box::new(left), op, Box::new(right)))
         }
 
@@ -408,7 +744,7 @@ impl Parser {
     }
 
     fn parse_comparison(&mut self) -> anyhow::Result<Expression> {
-        let mut left = self.parse_term()?;
+        let mut left = self.parse_range()?;
 
         while let TokenType::Greater
         | TokenType::GreaterEqual
@@ -416,13 +752,31 @@ impl Parser {
         | TokenType::LessEqual = self.peek().ty
         {
             let op = self.next_token().clone();
-            let right = self.parse_term()?;
+            let right = self.parse_range()?;
             left = Expression::Binary(Binary::new(Box::new(left), op, Box::new(right)))
         }
 
         Ok(left)
     }
 
+    // binds tighter than comparison and looser than arithmetic, so `a or b..c` is
+    // `a or (b..c)`, `1 + 2..3 * 4` is `(1 + 2)..(3 * 4)` and `x = 0..n` assigns the range
+    fn parse_range(&mut self) -> anyhow::Result<Expression> {
+        let left = self.parse_term()?;
+
+        if let TokenType::DotDot = self.peek().ty {
+            let op = self.next_token().clone();
+            let right = self.parse_term()?;
+            return Ok(Expression::Range(Range::new(
+                Box::new(left),
+                op,
+                Box::new(right),
+            )));
+        }
+
+        Ok(left)
+    }
+
     fn parse_term(&mut self) -> anyhow::Result<Expression> {
         let mut left = self.parse_factor()?;
 
@@ -436,21 +790,40 @@ impl Parser {
     }
 
     fn parse_factor(&mut self) -> anyhow::Result<Expression> {
-        let mut left = self.parse_unary()?;
+        let mut left = self.parse_power()?;
 
-        while let TokenType::Star | TokenType::Slash = self.peek().ty {
+        while let TokenType::Star | TokenType::Slash | TokenType::SlashDot | TokenType::Percent =
+            self.peek().ty
+        {
             let op = self.next_token().clone();
-            let right = self.parse_unary()?;
+            let right = self.parse_power()?;
             left = Expression::Binary(Binary::new(Box::new(left), op, Box::new(right)))
         }
 
         Ok(left)
     }
 
+    fn parse_power(&mut self) -> anyhow::Result<Expression> {
+        let left = self.parse_unary()?;
+
+        if let TokenType::StarStar = self.peek().ty {
+            let op = self.next_token().clone();
+            // recursing on the right side makes '**' right-associative
+            let right = self.nested(Self::parse_power)?;
+            return Ok(Expression::Binary(Binary::new(
+                Box::new(left),
+                op,
+                Box::new(right),
+            )));
+        }
+
+        Ok(left)
+    }
+
     fn parse_unary(&mut self) -> anyhow::Result<Expression> {
         if matches!(self.peek().ty, TokenType::Minus | TokenType::Bang) {
             let op = self.next_token().clone();
-            let expr = self.parse_primary()?;
+            let expr = self.nested(Self::parse_unary)?;
             return Ok(Expression::Unary(Unary::new(op, Box::new(expr))));
         }
 
@@ -472,9 +845,20 @@ impl Parser {
                         TokenType::Identifier,
                         "Expect property name after '.'",
                         self.peek_previous().line,
+                        self.peek_previous().column,
                     )?
                     .clone();
                 callee = Expression::Get(Get::new(Box::new(callee), field));
+            } else if let TokenType::LeftBracket = self.peek().ty {
+                let bracket = self.next_token().clone();
+                let index = self.parse_expression()?;
+                self.expect(
+                    TokenType::RightBracket,
+                    "Expected ']' after index",
+                    bracket.line,
+                    bracket.column,
+                )?;
+                callee = Expression::Index(Index::new(Box::new(callee), bracket, Box::new(index)));
             } else {
                 break;
             }
@@ -491,39 +875,259 @@ impl Parser {
             TokenType::False => Ok(Expression::Literal(Literal::Boolean(false))),
             TokenType::True => Ok(Expression::Literal(Literal::Boolean(true))),
             TokenType::Null => Ok(Expression::Literal(Literal::Null)),
-            TokenType::Identifier => Ok(Expression::Var(primary)),
+            TokenType::Identifier => Ok(var(primary)),
+            TokenType::This => Ok(Expression::This(primary)),
+            TokenType::Super => {
+                self.expect(
+                    TokenType::Dot,
+                    "Expected '.' after 'super'",
+                    primary.line,
+                    primary.column,
+                )?;
+                let method = self
+                    .expect(
+                        TokenType::Identifier,
+                        "Expected superclass method name after 'super.'",
+                        primary.line,
+                        primary.column,
+                    )?
+                    .clone();
+                Ok(Expression::Super(Super::new(primary, method)))
+            }
             TokenType::LeftParen => {
                 let expr = self.parse_expression()?;
                 self.expect(
                     TokenType::RightParen,
                     "Expected ')' after expression",
                     primary.line,
+                    primary.column,
                 )?;
                 Ok(Expression::Grouping(Box::new(expr)))
             }
+            // a `{` in expression position always starts an object literal, blocks are statements
+            TokenType::LeftBrace => self.parse_object_literal(primary),
+            TokenType::LeftBracket => self.parse_list_literal(primary),
+            TokenType::Fn => {
+                // lambdas are plain functions named `lambda`, at the keyword's position
+                let ident = Token::new(
+                    "lambda".to_string(),
+                    TokenType::Identifier,
+                    primary.line,
+                    primary.column,
+                );
+                let (params, variadic) = self.parse_fn_params()?;
+                let body = self.parse_fn_body()?;
+                Ok(Expression::Lambda(Box::new(FnDecl::new(
+                    ident, params, variadic, body,
+                ))))
+            }
             _ => bail!(syntax_error(
                 &primary.line,
-                &format!("Expected expression. Found {:?}", primary.lexeme)
+                &primary.column,
+                &format!("Expected expression, found {}", primary.ty)
             )),
         }
     }
 
-    fn parse_fn_params(&mut self) -> anyhow::Result<Vec<Token>> {
+    fn parse_map_literal(&mut self, brace_token: Token) -> anyhow::Result<Expression> {
+        if let TokenType::Colon = self.peek().ty {
+            self.next_token();
+            self.expect(
+                TokenType::RightBrace,
+                "Expected '}' after ':' in empty map literal",
+                brace_token.line,
+                brace_token.column,
+            )?;
+            return Ok(Expression::Map(MapLiteral::new(brace_token, Vec::new())));
+        }
+
+        let mut entries = Vec::new();
+        while !matches!(self.peek().ty, TokenType::RightBrace) && !self.finished() {
+            let key = match self.next_token().ty.clone() {
+                TokenType::String(key) => key,
+                _ => bail!(syntax_error(
+                    &brace_token.line,
+                    &brace_token.column,
+                    "Expected string key in map literal"
+                )),
+            };
+            self.expect(
+                TokenType::Colon,
+                "Expected ':' after key in map literal",
+                brace_token.line,
+                brace_token.column,
+            )?;
+            let value = self.parse_expression()?;
+            entries.push(MapEntry::new(key, value));
+
+            if let TokenType::Comma = self.peek().ty {
+                self.next_token();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(
+            TokenType::RightBrace,
+            "Expected '}' at end of map literal",
+            brace_token.line,
+            brace_token.column,
+        )?;
+        Ok(Expression::Map(MapLiteral::new(brace_token, entries)))
+    }
+
+    fn parse_list_literal(&mut self, bracket_token: Token) -> anyhow::Result<Expression> {
+        let mut items = Vec::new();
+        while !matches!(self.peek().ty, TokenType::RightBracket) && !self.finished() {
+            let item = self.parse_expression()?;
+            // `[expr for x in iterable]` builds the list from a loop instead
+            if items.is_empty() && self.peek().ty == TokenType::For {
+                return self.parse_comprehension(bracket_token, item);
+            }
+            items.push(item);
+
+            if let TokenType::Comma = self.peek().ty {
+                self.next_token();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(
+            TokenType::RightBracket,
+            "Expected ']' at end of list literal",
+            bracket_token.line,
+            bracket_token.column,
+        )?;
+        Ok(Expression::List(ListLiteral::new(bracket_token, items)))
+    }
+
+    fn parse_object_literal(&mut self, brace_token: Token) -> anyhow::Result<Expression> {
+        // `{"key": value}` and the empty `{:}` are maps, `{field = value}` are objects
+        let is_map = match self.peek().ty {
+            TokenType::Colon => true,
+            TokenType::String(_) => {
+                matches!(self.peek_next().map(|t| &t.ty), Some(TokenType::Colon))
+            }
+            _ => false,
+        };
+        if is_map {
+            return self.parse_map_literal(brace_token);
+        }
+
+        let mut fields = Vec::new();
+        while !matches!(self.peek().ty, TokenType::RightBrace) && !self.finished() {
+            let ident = self
+                .expect(
+                    TokenType::Identifier,
+                    "Expected field name in object literal",
+                    brace_token.line,
+                    brace_token.column,
+                )?
+                .clone();
+            self.expect(
+                TokenType::Equal,
+                "Expected '=' after field name in object literal",
+                ident.line,
+                ident.column,
+            )?;
+            let value = self.parse_expression()?;
+            fields.push(ObjectField::new(ident, value));
+
+            if let TokenType::Comma = self.peek().ty {
+                self.next_token();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(
+            TokenType::RightBrace,
+            "Expected '}' at end of object literal",
+            brace_token.line,
+            brace_token.column,
+        )?;
+        Ok(Expression::Object(ObjectLiteral::new(brace_token, fields)))
+    }
+
+    // `[expr for x in iterable if cond]`, where the `if` clause is optional
+    fn parse_comprehension(
+        &mut self,
+        bracket_token: Token,
+        expr: Expression,
+    ) -> anyhow::Result<Expression> {
+        let for_token = self.next_token().clone();
+        let variable = self
+            .expect(
+                TokenType::Identifier,
+                "Expected identifier after 'for' in list comprehension",
+                for_token.line,
+                for_token.column,
+            )?
+            .clone();
+        self.expect(
+            TokenType::In,
+            "Expected 'in' keyword after identifier in list comprehension",
+            variable.line,
+            variable.column,
+        )?;
+        let iterable = self.parse_expression()?;
+
+        let condition = if self.peek().ty == TokenType::If {
+            self.next_token();
+            Some(Box::new(self.parse_expression()?))
+        } else {
+            None
+        };
+
+        self.expect(
+            TokenType::RightBracket,
+            "Expected ']' at end of list comprehension",
+            bracket_token.line,
+            bracket_token.column,
+        )?;
+        Ok(Expression::Comprehension(Comprehension::new(
+            bracket_token,
+            Box::new(expr),
+            variable,
+            Box::new(iterable),
+            condition,
+        )))
+    }
+
+    // the parameters, and whether the last one is a `...rest` parameter
+    fn parse_fn_params(&mut self) -> anyhow::Result<(Vec<Token>, bool)> {
         let left_paren = self
             .expect(
                 TokenType::LeftParen,
                 "Expected '(' before function parameters",
                 self.peek_previous().line,
+                self.peek_previous().column,
             )?
             .clone();
 
         let mut params = Vec::new();
+        let mut variadic = false;
         while !matches!(self.peek().ty, TokenType::RightParen) {
+            if variadic {
+                bail!(syntax_error(
+                    &left_paren.line,
+                    &left_paren.column,
+                    "Rest parameter must be the last parameter"
+                ))
+            }
+
+            if let TokenType::Ellipsis = self.peek().ty {
+                self.next_token();
+                variadic = true;
+            }
+
             let arg = self
                 .expect(
                     TokenType::Identifier,
                     "Expected parameters identifiers",
                     left_paren.line,
+                    left_paren.column,
                 )?
                 .clone();
             params.push(arg);
@@ -536,8 +1140,9 @@ impl Parser {
             TokenType::RightParen,
             "Expected ')' after function parameters",
             self.peek_previous().line,
+            self.peek_previous().column,
         )?;
-        Ok(params)
+        Ok((params, variadic))
     }
 
     fn parse_fn_args(&mut self, e: Expression, paren_token: Token) -> anyhow::Result<Expression> {
@@ -554,6 +1159,7 @@ impl Parser {
             TokenType::RightParen,
             "Expected ')' after function arguments",
             self.peek_previous().line,
+            self.peek_previous().column,
         )?;
         Ok(Expression::Call(Call::new(Box::new(e), paren_token, args)))
     }
@@ -569,22 +1175,45 @@ impl Parser {
             match self.peek().ty {
                 TokenType::Class
                 | TokenType::Let
+                | TokenType::Const
                 | TokenType::Fn
                 | TokenType::For
                 | TokenType::While
                 | TokenType::If
+                | TokenType::Import
+                | TokenType::Try
+                | TokenType::Break
+                | TokenType::Continue
+                | TokenType::Export
                 | TokenType::Return => return,
                 _ => self.next_token(),
             };
         }
     }
 
-    fn expect(&mut self, ty: TokenType, msg: &str, line: usize) -> anyhow::Result<&Token> {
-        if self.peek().ty == ty {
+    fn expect(
+        &mut self,
+        ty: TokenType,
+        msg: &str,
+        line: usize,
+        column: usize,
+    ) -> anyhow::Result<&Token> {
+        if self.peek().ty == ty && !self.finished() {
             return Ok(self.next_token());
         }
 
-        bail!(syntax_error(&line, msg))
+        bail!(syntax_error(
+            &line,
+            &column,
+            &format!("{}, found {}", msg, self.found())
+        ))
+    }
+
+    fn found(&self) -> String {
+        if self.finished() {
+            return "end of file".to_string();
+        }
+        self.peek().ty.to_string()
     }
 
     fn finished(&self) -> bool {
@@ -597,6 +1226,10 @@ impl Parser {
             .unwrap_or_else(|| self.tokens.last().expect("Token list is empty"))
     }
 
+    fn peek_next(&self) -> Option<&Token> {
+        self.tokens.get(self.current + 1)
+    }
+
     fn peek_previous(&self) -> &Token {
         if self.current == 0 {
             panic!("No previous token available");
@@ -613,3 +1246,31 @@ impl Parser {
         token
     }
 }
+
+fn var(ident: Token) -> Expression {
+    Expression::Var(Variable::new(ident, Resolution::Unresolved))
+}
+
+fn describe_target(expr: &Expression) -> &'static str {
+    match expr {
+        Expression::Literal(Literal::Boolean(_)) => "a boolean literal",
+        Expression::Literal(Literal::Number(_)) => "a number literal",
+        Expression::Literal(Literal::Str(_)) => "a string literal",
+        Expression::Literal(Literal::Null) => "'null'",
+        Expression::Call(_) => "the result of a call",
+        Expression::Grouping(_) => "a parenthesized expression",
+        Expression::Unary(_) => "a unary expression",
+        Expression::Binary(_) => "a binary expression",
+        Expression::Logical(_) => "a logical expression",
+        Expression::Range(_) => "a range",
+        Expression::Lambda(_) => "a function expression",
+        Expression::Object(_) => "an object literal",
+        Expression::List(_) | Expression::Comprehension(_) => "a list literal",
+        Expression::Map(_) => "a map literal",
+        Expression::Assignment(_) | Expression::Set(_) | Expression::SetIndex(_) => "an assignment",
+        Expression::This(_) => "'this'",
+        Expression::Super(_) => "a superclass method",
+        // these are the valid targets, turned into an assignment before a target is described
+        Expression::Var(_) | Expression::Get(_) | Expression::Index(_) => unreachable!(),
+    }
+}
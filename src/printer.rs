@@ -0,0 +1,296 @@
+use crate::grammar::{Declaration, Expression, FnDecl, Literal, Statement};
+
+// Renders a program for `--ast`, to check how the parser grouped and desugared it.
+// Expressions are fully parenthesized in prefix form, `(+ 1 (* 2 3))`, while
+// declarations and statements take a line each, nested ones indented below them.
+pub fn pretty_print(declarations: &[Declaration]) -> String {
+    let mut out = String::new();
+    for decl in declarations {
+        declaration(decl, 0, &mut out);
+    }
+    out.trim_start().to_string()
+}
+
+// every line is written as a newline followed by its indentation, so a function
+// expression can carry its body inside the parentheses around it
+fn line(out: &mut String, indent: usize, text: &str) {
+    out.push('\n');
+    out.push_str(&"  ".repeat(indent));
+    out.push_str(text);
+}
+
+fn declaration(decl: &Declaration, indent: usize, out: &mut String) {
+    match decl {
+        Declaration::StmtDecl(stmt_decl) => statement(&stmt_decl.stmt, indent, out),
+        Declaration::LetDecl(let_decl) => {
+            let keyword = if let_decl.constant { "const" } else { "let" };
+            let text = match &let_decl.init {
+                Some(init) => format!(
+                    "{} {} {}",
+                    keyword,
+                    let_decl.ident.lexeme,
+                    expression(init, indent)
+                ),
+                None => format!("{} {}", keyword, let_decl.ident.lexeme),
+            };
+            line(out, indent, &text)
+        }
+        Declaration::FnDecl(fn_decl) => {
+            line(
+                out,
+                indent,
+                &format!("fn {} {}", fn_decl.ident.lexeme, params(fn_decl)),
+            );
+            body(fn_decl, indent + 1, out)
+        }
+        Declaration::ClassDecl(class_decl) => {
+            let text = match &class_decl.superclass {
+                Some(superclass) => {
+                    format!("class {} < {}", class_decl.ident.lexeme, superclass.lexeme)
+                }
+                None => format!("class {}", class_decl.ident.lexeme),
+            };
+            line(out, indent, &text);
+            for method in &class_decl.methods {
+                line(
+                    out,
+                    indent + 1,
+                    &format!("fn {} {}", method.ident.lexeme, params(method)),
+                );
+                body(method, indent + 2, out)
+            }
+        }
+        Declaration::ExportDecl(export_decl) => {
+            line(out, indent, "export");
+            declaration(&export_decl.decl, indent + 1, out)
+        }
+    }
+}
+
+fn params(fn_decl: &FnDecl) -> String {
+    let mut params = fn_decl
+        .params
+        .iter()
+        .map(|param| param.lexeme.clone())
+        .collect::<Vec<_>>();
+    if fn_decl.variadic {
+        if let Some(rest) = params.last_mut() {
+            rest.insert_str(0, "...");
+        }
+    }
+    format!("({})", params.join(" "))
+}
+
+// the statements of a function body, without the block around them
+fn body(fn_decl: &FnDecl, indent: usize, out: &mut String) {
+    match &fn_decl.body {
+        Statement::BlockStmt(block) => {
+            for decl in &block.stmts {
+                declaration(decl, indent, out);
+            }
+        }
+        stmt => statement(stmt, indent, out),
+    }
+}
+
+fn statement(stmt: &Statement, indent: usize, out: &mut String) {
+    match stmt {
+        Statement::ExprStmt(expr_stmt) => line(out, indent, &expression(&expr_stmt.expr, indent)),
+        Statement::BlockStmt(block_stmt) => {
+            line(out, indent, "block");
+            for decl in &block_stmt.stmts {
+                declaration(decl, indent + 1, out);
+            }
+        }
+        Statement::IfStmt(if_stmt) => {
+            line(
+                out,
+                indent,
+                &format!("if {}", expression(&if_stmt.condition, indent)),
+            );
+            statement(&if_stmt.if_branch, indent + 1, out);
+            if let Some(else_branch) = &if_stmt.else_branch {
+                line(out, indent, "else");
+                statement(else_branch, indent + 1, out);
+            }
+        }
+        Statement::WhileStmt(while_stmt) => {
+            line(
+                out,
+                indent,
+                &format!("while {}", expression(&while_stmt.condition, indent)),
+            );
+            statement(&while_stmt.body, indent + 1, out);
+            if let Some(increment) = &while_stmt.increment {
+                line(
+                    out,
+                    indent + 1,
+                    &format!("increment {}", expression(increment, indent + 1)),
+                );
+            }
+        }
+        Statement::ReturnStmt(return_stmt) => match &return_stmt.expr {
+            Some(expr) => line(out, indent, &format!("return {}", expression(expr, indent))),
+            None => line(out, indent, "return"),
+        },
+        Statement::BreakStmt(_) => line(out, indent, "break"),
+        Statement::ContinueStmt(_) => line(out, indent, "continue"),
+        Statement::ImportStmt(import_stmt) => {
+            let text = match &import_stmt.alias {
+                Some(alias) => format!("import {:?} as {}", import_stmt.path, alias.lexeme),
+                None => format!("import {:?}", import_stmt.path),
+            };
+            line(out, indent, &text)
+        }
+        Statement::TryStmt(try_stmt) => {
+            line(out, indent, "try");
+            statement(&try_stmt.body, indent + 1, out);
+            if let Some(catch_body) = &try_stmt.catch_body {
+                match &try_stmt.catch_ident {
+                    Some(ident) => line(out, indent, &format!("catch {}", ident.lexeme)),
+                    None => line(out, indent, "catch"),
+                }
+                statement(catch_body, indent + 1, out);
+            }
+            if let Some(finally_body) = &try_stmt.finally_body {
+                line(out, indent, "finally");
+                statement(finally_body, indent + 1, out);
+            }
+        }
+    }
+}
+
+// `indent` is that of the line the expression is on, for the body of a function expression
+fn expression(expr: &Expression, indent: usize) -> String {
+    let list = |head: &str, items: Vec<String>| {
+        if items.is_empty() {
+            format!("({})", head)
+        } else {
+            format!("({} {})", head, items.join(" "))
+        }
+    };
+
+    match expr {
+        Expression::Literal(literal) => match literal {
+            Literal::Boolean(b) => b.to_string(),
+            Literal::Number(n) => n.to_string(),
+            Literal::Str(s) => format!("{:?}", s),
+            Literal::Null => "null".to_string(),
+        },
+        Expression::Var(var) => var.ident.lexeme.clone(),
+        Expression::This(_) => "this".to_string(),
+        Expression::Super(sup) => format!("(super {})", sup.method.lexeme),
+        Expression::Call(call) => {
+            let mut items = vec![expression(&call.callee, indent)];
+            items.extend(call.args.iter().map(|arg| expression(arg, indent)));
+            list("call", items)
+        }
+        Expression::Get(get) => {
+            format!(
+                "(. {} {})",
+                expression(&get.object, indent),
+                get.field.lexeme
+            )
+        }
+        Expression::Set(set) => format!(
+            "(= (. {} {}) {})",
+            expression(&set.object, indent),
+            set.field.lexeme,
+            expression(&set.value, indent)
+        ),
+        Expression::Index(index) => format!(
+            "([] {} {})",
+            expression(&index.object, indent),
+            expression(&index.index, indent)
+        ),
+        Expression::SetIndex(set) => {
+            let operator = match &set.operator {
+                Some(operator) => format!("{}=", operator.lexeme),
+                None => "=".to_string(),
+            };
+            format!(
+                "({} ([] {} {}) {})",
+                operator,
+                expression(&set.object, indent),
+                expression(&set.index, indent),
+                expression(&set.value, indent)
+            )
+        }
+        Expression::List(list_literal) => list(
+            "list",
+            list_literal
+                .items
+                .iter()
+                .map(|item| expression(item, indent))
+                .collect(),
+        ),
+        Expression::Comprehension(comp) => {
+            let mut items = vec![
+                expression(&comp.expr, indent),
+                format!(
+                    "(for {} {})",
+                    comp.variable.lexeme,
+                    expression(&comp.iterable, indent)
+                ),
+            ];
+            if let Some(condition) = &comp.condition {
+                items.push(format!("(if {})", expression(condition, indent)));
+            }
+            list("comprehension", items)
+        }
+        Expression::Unary(unary) => format!(
+            "({} {})",
+            unary.operator.lexeme,
+            expression(&unary.expr, indent)
+        ),
+        Expression::Logical(logical) => format!(
+            "({} {} {})",
+            logical.operator.lexeme,
+            expression(&logical.left, indent),
+            expression(&logical.right, indent)
+        ),
+        Expression::Binary(binary) => format!(
+            "({} {} {})",
+            binary.operator.lexeme,
+            expression(&binary.left, indent),
+            expression(&binary.right, indent)
+        ),
+        Expression::Range(range) => format!(
+            "(.. {} {})",
+            expression(&range.left, indent),
+            expression(&range.right, indent)
+        ),
+        Expression::Grouping(expr) => format!("(group {})", expression(expr, indent)),
+        Expression::Lambda(lambda) => {
+            let mut out = String::new();
+            body(lambda, indent + 1, &mut out);
+            format!("(fn {}{})", params(lambda), out)
+        }
+        Expression::Object(object) => list(
+            "object",
+            object
+                .fields
+                .iter()
+                .map(|field| {
+                    format!(
+                        "(= {} {})",
+                        field.ident.lexeme,
+                        expression(&field.value, indent)
+                    )
+                })
+                .collect(),
+        ),
+        Expression::Map(map) => list(
+            "map",
+            map.entries
+                .iter()
+                .map(|entry| format!("({:?} {})", entry.key, expression(&entry.value, indent)))
+                .collect(),
+        ),
+        Expression::Assignment(assignment) => format!(
+            "(= {} {})",
+            assignment.ident.lexeme,
+            expression(&assignment.expr, indent)
+        ),
+    }
+}
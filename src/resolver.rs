@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+
+use crate::{
+    error::{syntax_error, ErrorReport},
+    grammar::{Declaration, Expression, FnDecl, Resolution, Statement},
+    token::Token,
+};
+
+// Works out, before anything runs, which scope every variable use refers to, so a
+// closure keeps seeing the binding it was written against even when a later
+// declaration shadows it. The scopes pushed here mirror the environments the
+// interpreter creates, one per block, call, bound method and superclass.
+pub struct Resolver {
+    // innermost last, mapping each name to whether it was declared `const`;
+    // empty at the top level, whose names live in the global environment
+    scopes: Vec<HashMap<String, bool>>,
+    // the kind of function whose body is being resolved, `return` is only valid inside one
+    current_function: FunctionType,
+    // whether a class body encloses the code being resolved, `this` and `super` are only
+    // valid inside one
+    current_class: ClassType,
+    // how many of `scopes` already existed when resolving began, see `within`
+    enclosing: usize,
+    errors: ErrorReport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Method,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            scopes: Vec::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+            enclosing: 0,
+            errors: ErrorReport::default(),
+        }
+    }
+
+    // for source run inside local scopes that already exist, as `eval` and breakpoints
+    // do, given the names bound in each of them from the outermost inwards
+    pub fn within(scopes: Vec<Vec<String>>) -> Self {
+        let enclosing = scopes.len();
+        // a method's receiver is bound in a scope of its own around the call
+        let current_class = match scopes.iter().flatten().any(|name| name == "this") {
+            true => ClassType::Class,
+            false => ClassType::None,
+        };
+        let scopes = scopes
+            .into_iter()
+            .map(|names| names.into_iter().map(|name| (name, false)).collect())
+            .collect();
+        Self {
+            scopes,
+            current_class,
+            enclosing,
+            ..Self::new()
+        }
+    }
+
+    pub fn resolve(&mut self, declarations: &mut [Declaration]) -> anyhow::Result<()> {
+        for decl in declarations {
+            if let Err(e) = self.resolve_declaration(decl) {
+                self.errors.collect(e, usize::MAX, 0, 0);
+                // an error can leave the scopes of the declaration it came from open
+                self.scopes.truncate(self.enclosing);
+                self.current_function = FunctionType::None;
+                self.current_class = ClassType::None;
+            }
+        }
+
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+
+        bail!(self.errors.clone())
+    }
+
+    fn resolve_declaration(&mut self, decl: &mut Declaration) -> anyhow::Result<()> {
+        match decl {
+            Declaration::StmtDecl(stmt_decl) => self.resolve_statement(&mut stmt_decl.stmt),
+            Declaration::LetDecl(let_decl) => {
+                // the initializer runs before the name is bound, so it sees any outer one
+                if let Some(init) = &mut let_decl.init {
+                    self.resolve_expression(init)?;
+                }
+                // `let _ = expr;` binds nothing
+                if let_decl.ident.lexeme != "_" {
+                    self.declare(&let_decl.ident, let_decl.constant)?;
+                }
+                Ok(())
+            }
+            Declaration::FnDecl(fn_decl) => {
+                // declared first so the body can call itself
+                self.declare(&fn_decl.ident, false)?;
+                self.resolve_function(fn_decl, FunctionType::Function)
+            }
+            Declaration::ClassDecl(class_decl) => {
+                self.declare(&class_decl.ident, false)?;
+                let inherits = class_decl.superclass.is_some();
+                let enclosing = std::mem::replace(&mut self.current_class, ClassType::Class);
+                for method in &mut class_decl.methods {
+                    if inherits {
+                        self.begin_scope(&["super"]);
+                    }
+                    self.begin_scope(&["this"]);
+                    self.resolve_function(method, FunctionType::Method)?;
+                    self.end_scope();
+                    if inherits {
+                        self.end_scope();
+                    }
+                }
+                self.current_class = enclosing;
+                Ok(())
+            }
+            Declaration::ExportDecl(export_decl) => self.resolve_declaration(&mut export_decl.decl),
+        }
+    }
+
+    // parameters and the top level of the body share the scope a call creates
+    fn resolve_function(&mut self, fn_decl: &mut FnDecl, kind: FunctionType) -> anyhow::Result<()> {
+        let enclosing = std::mem::replace(&mut self.current_function, kind);
+        let params = fn_decl
+            .params
+            .iter()
+            .map(|param| param.lexeme.as_str())
+            .collect::<Vec<_>>();
+        self.begin_scope(&params);
+        if let Statement::BlockStmt(block) = &mut fn_decl.body {
+            for decl in &mut block.stmts {
+                self.resolve_declaration(decl)?;
+            }
+        }
+        self.end_scope();
+        self.current_function = enclosing;
+        Ok(())
+    }
+
+    fn resolve_statement(&mut self, stmt: &mut Statement) -> anyhow::Result<()> {
+        match stmt {
+            Statement::ExprStmt(expr_stmt) => self.resolve_expression(&mut expr_stmt.expr),
+            Statement::BlockStmt(block_stmt) => {
+                self.begin_scope(&[]);
+                for decl in &mut block_stmt.stmts {
+                    self.resolve_declaration(decl)?;
+                }
+                self.end_scope();
+                Ok(())
+            }
+            Statement::IfStmt(if_stmt) => {
+                self.resolve_expression(&mut if_stmt.condition)?;
+                self.resolve_statement(&mut if_stmt.if_branch)?;
+                if let Some(else_branch) = &mut if_stmt.else_branch {
+                    self.resolve_statement(else_branch)?;
+                }
+                Ok(())
+            }
+            Statement::WhileStmt(while_stmt) => {
+                self.resolve_expression(&mut while_stmt.condition)?;
+                self.resolve_statement(&mut while_stmt.body)?;
+                if let Some(increment) = &mut while_stmt.increment {
+                    self.resolve_expression(increment)?;
+                }
+                Ok(())
+            }
+            Statement::ReturnStmt(return_stmt) => {
+                if self.current_function == FunctionType::None {
+                    let token = &return_stmt.return_token;
+                    bail!(syntax_error(
+                        &token.line,
+                        &token.column,
+                        "Can't return from outside a function"
+                    ))
+                }
+                match &mut return_stmt.expr {
+                    Some(expr) => self.resolve_expression(expr),
+                    None => Ok(()),
+                }
+            }
+            Statement::TryStmt(try_stmt) => {
+                self.resolve_statement(&mut try_stmt.body)?;
+                // the caught error is bound in the same scope as the catch block's contents
+                if let Some(Statement::BlockStmt(block)) = try_stmt.catch_body.as_deref_mut() {
+                    self.begin_scope(&[]);
+                    if let Some(ident) = &try_stmt.catch_ident {
+                        self.declare(ident, false)?;
+                    }
+                    for decl in &mut block.stmts {
+                        self.resolve_declaration(decl)?;
+                    }
+                    self.end_scope();
+                }
+                if let Some(finally_body) = &mut try_stmt.finally_body {
+                    self.resolve_statement(finally_body)?;
+                }
+                Ok(())
+            }
+            // a plain import adds its names to the global scope
+            Statement::ImportStmt(import_stmt) => {
+                if let Some(alias) = &import_stmt.alias {
+                    self.declare(alias, false)?;
+                }
+                Ok(())
+            }
+            Statement::BreakStmt(_) | Statement::ContinueStmt(_) => Ok(()),
+        }
+    }
+
+    fn resolve_expression(&mut self, expr: &mut Expression) -> anyhow::Result<()> {
+        match expr {
+            Expression::Literal(_) => Ok(()),
+            Expression::This(keyword) => self.check_in_class(keyword),
+            Expression::Super(sup) => self.check_in_class(&sup.keyword),
+            Expression::Var(var) => {
+                var.resolution = self.resolve_local(&var.ident).0;
+                Ok(())
+            }
+            Expression::Assignment(assignment) => {
+                self.resolve_expression(&mut assignment.expr)?;
+                let (resolution, constant) = self.resolve_local(&assignment.ident);
+                if constant {
+                    let ident = &assignment.ident;
+                    bail!(syntax_error(
+                        &ident.line,
+                        &ident.column,
+                        &format!("Cannot assign to constant '{}'", ident.lexeme)
+                    ))
+                }
+                assignment.resolution = resolution;
+                Ok(())
+            }
+            Expression::Call(call) => {
+                self.resolve_expression(&mut call.callee)?;
+                for arg in &mut call.args {
+                    self.resolve_expression(arg)?;
+                }
+                Ok(())
+            }
+            Expression::Get(get) => self.resolve_expression(&mut get.object),
+            Expression::Index(index) => {
+                self.resolve_expression(&mut index.object)?;
+                self.resolve_expression(&mut index.index)
+            }
+            Expression::SetIndex(set) => {
+                self.resolve_expression(&mut set.object)?;
+                self.resolve_expression(&mut set.index)?;
+                self.resolve_expression(&mut set.value)
+            }
+            Expression::List(list) => {
+                for item in &mut list.items {
+                    self.resolve_expression(item)?;
+                }
+                Ok(())
+            }
+            // the variable lives in a scope of its own, which the iterable is outside of
+            Expression::Comprehension(comp) => {
+                self.resolve_expression(&mut comp.iterable)?;
+                self.begin_scope(&[&comp.variable.lexeme]);
+                if let Some(condition) = &mut comp.condition {
+                    self.resolve_expression(condition)?;
+                }
+                self.resolve_expression(&mut comp.expr)?;
+                self.end_scope();
+                Ok(())
+            }
+            Expression::Unary(unary) => self.resolve_expression(&mut unary.expr),
+            Expression::Set(set) => {
+                self.resolve_expression(&mut set.object)?;
+                self.resolve_expression(&mut set.value)
+            }
+            Expression::Logical(logical) => {
+                self.resolve_expression(&mut logical.left)?;
+                self.resolve_expression(&mut logical.right)
+            }
+            Expression::Binary(binary) => {
+                self.resolve_expression(&mut binary.left)?;
+                self.resolve_expression(&mut binary.right)
+            }
+            Expression::Range(range) => {
+                self.resolve_expression(&mut range.left)?;
+                self.resolve_expression(&mut range.right)
+            }
+            Expression::Grouping(expr) => self.resolve_expression(expr),
+            Expression::Lambda(lambda) => self.resolve_function(lambda, FunctionType::Function),
+            Expression::Object(object) => {
+                for field in &mut object.fields {
+                    self.resolve_expression(&mut field.value)?;
+                }
+                Ok(())
+            }
+            Expression::Map(map) => {
+                for entry in &mut map.entries {
+                    self.resolve_expression(&mut entry.value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // the distance to the innermost scope declaring the name, and whether it is constant;
+    // names no local scope declares are global
+    fn resolve_local(&self, ident: &Token) -> (Resolution, bool) {
+        for (distance, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(constant) = scope.get(&ident.lexeme) {
+                return (Resolution::Local(distance), *constant);
+            }
+        }
+        (Resolution::Global, false)
+    }
+
+    fn check_in_class(&self, keyword: &Token) -> anyhow::Result<()> {
+        if self.current_class == ClassType::None {
+            bail!(syntax_error(
+                &keyword.line,
+                &keyword.column,
+                &format!("Can't use '{}' outside of a class method", keyword.lexeme)
+            ))
+        }
+        Ok(())
+    }
+
+    // redeclaring a name in the same local scope is most likely a mistake, while the
+    // top level may rebind freely
+    fn declare(&mut self, ident: &Token, constant: bool) -> anyhow::Result<()> {
+        if let Some(scope) = self.scopes.last_mut() {
+            if scope.contains_key(&ident.lexeme) {
+                bail!(syntax_error(
+                    &ident.line,
+                    &ident.column,
+                    &format!("Variable '{}' already declared in this scope", ident.lexeme)
+                ))
+            }
+            scope.insert(ident.lexeme.clone(), constant);
+        }
+        Ok(())
+    }
+
+    fn begin_scope(&mut self, names: &[&str]) {
+        let scope = names.iter().map(|name| (name.to_string(), false)).collect();
+        self.scopes.push(scope);
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     env::Environment,
-    error::{runtime_error, Return},
+    error::{runtime_error, Signal},
     grammar::{FnDecl, Statement},
     interpreter::Interpreter,
     token::Token,
@@ -9,41 +9,296 @@ use anyhow::bail;
 use core::f64;
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Display},
     ops,
     rc::Rc,
 };
 
+// `strict_float` displays whole numbers with a trailing `.0` (`3.0` instead of `3`)
+fn format_number(n: f64, strict_float: bool) -> String {
+    if strict_float && n.is_finite() && n.fract() == 0.0 {
+        return format!("{:.1}", n);
+    }
+    n.to_string()
+}
+
+// checked before allocating so a huge count fails cleanly
+fn repetition_count(n: f64, len: usize, max_size: usize) -> anyhow::Result<usize> {
+    if n < 0.0 || n.fract() != 0.0 {
+        bail!("Expected a non-negative integer repetition count")
+    }
+
+    if len as f64 * n > max_size as f64 {
+        bail!("Repetition result too large")
+    }
+
+    Ok(n as usize)
+}
+
+// the length of a string in unicode scalars, the unit every string operation uses
+pub fn str_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+// validates a `start..end` slice of a sequence of length `len`
+pub fn slice_bounds(
+    start: f64,
+    end: f64,
+    len: usize,
+    line: &usize,
+    column: &usize,
+) -> anyhow::Result<(usize, usize)> {
+    if start.fract() != 0.0 || end.fract() != 0.0 {
+        bail!(runtime_error(
+            line,
+            column,
+            &format!("Expected integer slice bounds, found {}..{}", start, end)
+        ))
+    }
+
+    if start < 0.0 || start > end || end > len as f64 {
+        bail!(runtime_error(
+            line,
+            column,
+            &format!("Slice {}..{} out of bounds for length {}", start, end, len)
+        ))
+    }
+
+    Ok((start as usize, end as usize))
+}
+
+// `open` holds the addresses of the lists, maps and objects being printed, so one
+// that contains itself is shown as `[...]` or `{...}` rather than recursed into forever
+fn format_value(value: &Object, strict_float: bool, open: &mut HashSet<*const ()>) -> String {
+    match value {
+        Object::Str(s) => s.to_string(),
+        Object::Boolean(b) => b.to_string(),
+        Object::Number(n) => format_number(*n, strict_float),
+        Object::Null => "null".to_string(),
+        Object::Callable(callable) => callable.to_string(),
+        Object::Instance(instance) => format_instance(instance, strict_float, open),
+        Object::Module(module) => format!("<module {}>", module.ident),
+        Object::Range(start, end) => format!(
+            "{}..{}",
+            format_number(*start, strict_float),
+            format_number(*end, strict_float)
+        ),
+        Object::List(list) => format_list(list, strict_float, open),
+        Object::Map(map) => format_map(map, strict_float, open),
+    }
+}
+
+fn format_list(
+    list: &Rc<RefCell<Vec<Object>>>,
+    strict_float: bool,
+    open: &mut HashSet<*const ()>,
+) -> String {
+    let ptr = Rc::as_ptr(list) as *const ();
+    if !open.insert(ptr) {
+        return "[...]".to_string();
+    }
+
+    let items = list
+        .borrow()
+        .iter()
+        .map(|item| format_value(item, strict_float, open))
+        .collect::<Vec<_>>()
+        .join(", ");
+    open.remove(&ptr);
+    format!("[{}]", items)
+}
+
+// entries sorted by key so output is stable
+fn format_map(
+    map: &Rc<RefCell<HashMap<String, Object>>>,
+    strict_float: bool,
+    open: &mut HashSet<*const ()>,
+) -> String {
+    let ptr = Rc::as_ptr(map) as *const ();
+    if !open.insert(ptr) {
+        return "{...}".to_string();
+    }
+
+    let mut entries = map
+        .borrow()
+        .iter()
+        .map(|(key, value)| format!("\"{}\": {}", key, format_value(value, strict_float, open)))
+        .collect::<Vec<_>>();
+    entries.sort();
+    open.remove(&ptr);
+    format!("{{{}}}", entries.join(", "))
+}
+
+// instances of a class print as such, only object literals show their fields
+fn format_instance(
+    instance: &Rc<RefCell<Instance>>,
+    strict_float: bool,
+    open: &mut HashSet<*const ()>,
+) -> String {
+    let instance_ref = instance.borrow();
+    if !instance_ref.class.ident.is_empty() {
+        return format!("<{} instance>", instance_ref.class.ident);
+    }
+
+    let ptr = Rc::as_ptr(instance) as *const ();
+    if !open.insert(ptr) {
+        return "{...}".to_string();
+    }
+
+    let mut fields = instance_ref
+        .fields
+        .iter()
+        .map(|(ident, value)| format!("{} = {}", ident, format_value(value, strict_float, open)))
+        .collect::<Vec<_>>();
+    fields.sort();
+    open.remove(&ptr);
+    format!("{{{}}}", fields.join(", "))
+}
+
+// `open` holds the pairs of lists or maps being compared. A pair met again while
+// still open is taken as equal, which lets cyclic structures compare at all
+fn values_equal(a: &Object, b: &Object, open: &mut HashSet<(*const (), *const ())>) -> bool {
+    match (a, b) {
+        (Object::List(a), Object::List(b)) => {
+            if Rc::ptr_eq(a, b) {
+                return true;
+            }
+            let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+            if !open.insert(pair) {
+                return true;
+            }
+
+            let (a, b) = (a.borrow(), b.borrow());
+            let equal = a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(a, b)| values_equal(a, b, open));
+            open.remove(&pair);
+            equal
+        }
+        (Object::Map(a), Object::Map(b)) => {
+            if Rc::ptr_eq(a, b) {
+                return true;
+            }
+            let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+            if !open.insert(pair) {
+                return true;
+            }
+
+            let (a, b) = (a.borrow(), b.borrow());
+            let equal = a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| values_equal(a, b, open)));
+            open.remove(&pair);
+            equal
+        }
+        (a, b) => a == b,
+    }
+}
+
 pub trait Callable {
     fn call(&mut self, interp: &mut Interpreter, args: Vec<Object>) -> anyhow::Result<Object>;
     fn arity(&self) -> usize;
+    // callables taking optional trailing arguments accept anything from `arity` up to this
+    fn max_arity(&self) -> usize {
+        self.arity()
+    }
     fn to_string(&self) -> String;
-    fn clone_box(&self) -> Box<dyn Callable + Send + Sync + 'static>;
+    fn clone_box(&self) -> Box<dyn Callable>;
+    // the class behind a callable, for callables that construct instances
+    fn class(&self) -> Option<Class> {
+        None
+    }
 }
 
 pub enum Object {
+    // lengths, indices and slices of strings count unicode scalars, never bytes
     Str(String),
     Boolean(bool),
     Number(f64),
-    Callable(Box<dyn Callable + Send + Sync + 'static>),
-    Instance(Instance),
+    Callable(Box<dyn Callable>),
+    Instance(Rc<RefCell<Instance>>),
+    Module(Rc<Module>),
+    // start and end bounds, the end being exclusive
+    Range(f64, f64),
+    List(Rc<RefCell<Vec<Object>>>),
+    Map(Rc<RefCell<HashMap<String, Object>>>),
     Null,
 }
 
+#[derive(Clone)]
 pub struct Function {
     pub declaration: FnDecl,
+    pub closure: Rc<RefCell<Environment>>,
+}
+
+// A method paired with the instance it was accessed on; `this` resolves to the receiver.
+pub struct BoundMethod {
+    pub receiver: Object,
+    pub method: Function,
 }
 
-#[derive(Clone)]
 pub struct Instance {
     class: Class,
     fields: HashMap<String, Object>,
 }
 
+pub struct Module {
+    pub ident: String,
+    members: HashMap<String, Object>,
+}
+
 #[derive(Clone)]
 pub struct Class {
     pub ident: String,
+    pub methods: Rc<HashMap<String, Function>>,
+    pub superclass: Option<Superclass>,
+}
+
+#[derive(Clone)]
+pub enum Superclass {
+    Class(Rc<Class>),
+    // the declared superclass did not name a class, reported on instantiation
+    Unresolved(Token),
+}
+
+impl Class {
+    // the class of instances built by object literals, which print their fields
+    pub fn anonymous() -> Self {
+        Self {
+            ident: String::new(),
+            methods: Rc::new(HashMap::new()),
+            superclass: None,
+        }
+    }
+
+    // looks the method up on this class, then up the superclass chain
+    pub fn find_method(&self, key: &str) -> Option<Function> {
+        if let Some(method) = self.methods.get(key) {
+            return Some(method.clone());
+        }
+
+        match &self.superclass {
+            Some(Superclass::Class(superclass)) => superclass.find_method(key),
+            _ => None,
+        }
+    }
+
+    fn check_superclass(&self) -> anyhow::Result<()> {
+        match &self.superclass {
+            Some(Superclass::Class(superclass)) => superclass.check_superclass(),
+            Some(Superclass::Unresolved(ident)) => bail!(runtime_error(
+                &ident.line,
+                &ident.column,
+                &format!(
+                    "Superclass '{}' of '{}' is not a class",
+                    ident.lexeme, self.ident
+                )
+            )),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Instance {
@@ -61,6 +316,7 @@ impl Instance {
 
         bail!(runtime_error(
             &key.line,
+            &key.column,
             &format!("Undefined field {}", key.lexeme)
         ))
     }
@@ -68,92 +324,288 @@ impl Instance {
     pub fn set(&mut self, key: Token, value: Object) {
         self.fields.insert(key.lexeme, value);
     }
+
+    // fields shadow methods of the same name
+    pub fn method(&self, key: &str) -> Option<Function> {
+        if self.fields.contains_key(key) {
+            return None;
+        }
+        self.class.find_method(key)
+    }
+}
+
+impl Module {
+    pub fn new(ident: String, members: HashMap<String, Object>) -> Self {
+        Self { ident, members }
+    }
+
+    pub fn get(&self, key: &Token) -> anyhow::Result<Object> {
+        match self.members.get(&key.lexeme) {
+            Some(member) => Ok(member.clone()),
+            None => bail!(runtime_error(
+                &key.line,
+                &key.column,
+                &format!("Module '{}' has no member '{}'", self.ident, key.lexeme)
+            )),
+        }
+    }
 }
 
 impl Callable for Class {
-    fn call(&mut self, _: &mut Interpreter, _: Vec<Object>) -> anyhow::Result<Object> {
-        Ok(Object::Instance(Instance::new(Self {
-            ident: self.ident.clone(),
-        })))
+    fn call(&mut self, interp: &mut Interpreter, args: Vec<Object>) -> anyhow::Result<Object> {
+        self.check_superclass()?;
+        let instance = Object::Instance(Rc::new(RefCell::new(Instance::new(self.clone()))));
+
+        // `init` runs against the new instance, its return value is discarded
+        if let Some(init) = self.find_method("init") {
+            let mut init = BoundMethod {
+                receiver: instance.clone(),
+                method: init,
+            };
+            init.call(interp, args)?;
+        }
+
+        Ok(instance)
     }
 
     fn arity(&self) -> usize {
-        0
+        self.find_method("init").map_or(0, |init| init.arity())
+    }
+
+    fn max_arity(&self) -> usize {
+        self.find_method("init").map_or(0, |init| init.max_arity())
     }
 
     fn to_string(&self) -> String {
         format!("<class {}>", self.ident.clone())
     }
 
-    fn clone_box(&self) -> Box<dyn Callable + Send + Sync + 'static> {
-        Box::new(Class {
-            ident: self.ident.clone(),
-        })
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(self.clone())
+    }
+
+    fn class(&self) -> Option<Class> {
+        Some(self.clone())
     }
 }
 
-impl Callable for Function {
-    fn call(&mut self, interp: &mut Interpreter, args: Vec<Object>) -> anyhow::Result<Object> {
-        let env = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(
-            &interp.current,
-        )))));
-
-        for idx in 0..self.declaration.params.len() {
-            let param = self.declaration.params[idx].lexeme.clone();
-            let value = args[idx].clone();
-            RefCell::borrow_mut(&env).define(param, value);
+impl Function {
+    fn invoke(&mut self, interp: &mut Interpreter, args: Vec<Object>) -> anyhow::Result<Object> {
+        let params = &self.declaration.params;
+        let mut env = Environment::with_capacity(Some(Rc::clone(&self.closure)), params.len());
+
+        let mut args = args.into_iter();
+        for (idx, param) in params.iter().enumerate() {
+            let value = if self.declaration.variadic && idx == params.len() - 1 {
+                // a rest parameter collects every remaining argument
+                Object::List(Rc::new(RefCell::new(args.by_ref().collect())))
+            } else {
+                args.next().unwrap_or(Object::Null)
+            };
+            env.define(param.lexeme.clone(), value);
         }
+        let env = Rc::new(RefCell::new(env));
 
         if let Statement::BlockStmt(b) = &self.declaration.body {
             if let Err(e) = interp.exec_block_statement(&b, env) {
-                return match e.downcast::<Return>()?.value {
-                    Some(o) => Ok(o),
-                    None => Ok(Object::Null),
+                return match e.downcast::<Signal>() {
+                    Ok(Signal::Return) => Ok(interp.return_value.take().unwrap_or(Object::Null)),
+                    Ok(signal) => Err(signal.into()),
+                    Err(e) => Err(e),
                 };
             }
         }
 
         Ok(Object::Null)
     }
+}
+
+impl Callable for Function {
+    fn call(&mut self, interp: &mut Interpreter, args: Vec<Object>) -> anyhow::Result<Object> {
+        let ident = self.declaration.ident.lexeme.clone();
+        interp.trace_enter(&ident, &args);
+        let result = self.invoke(interp, args);
+        interp.trace_exit(&ident, &result);
+        result
+    }
 
     fn arity(&self) -> usize {
-        self.declaration.params.len()
+        self.declaration.params.len() - self.declaration.variadic as usize
+    }
+
+    fn max_arity(&self) -> usize {
+        if self.declaration.variadic {
+            return usize::MAX;
+        }
+        self.arity()
     }
 
     fn to_string(&self) -> String {
         format!("<user fn {}>", self.declaration.ident.lexeme)
     }
 
-    fn clone_box(&self) -> Box<dyn Callable + Send + Sync + 'static> {
+    fn clone_box(&self) -> Box<dyn Callable> {
         Box::new(Function {
             declaration: self.declaration.clone(),
+            closure: Rc::clone(&self.closure),
+        })
+    }
+}
+
+impl Callable for BoundMethod {
+    fn call(&mut self, interp: &mut Interpreter, args: Vec<Object>) -> anyhow::Result<Object> {
+        let mut env = Environment::new(Some(Rc::clone(&self.method.closure)));
+        env.define("this".to_string(), self.receiver.clone());
+
+        let mut method = Function {
+            declaration: self.method.declaration.clone(),
+            closure: Rc::new(RefCell::new(env)),
+        };
+        method.call(interp, args)
+    }
+
+    fn arity(&self) -> usize {
+        self.method.arity()
+    }
+
+    fn max_arity(&self) -> usize {
+        self.method.max_arity()
+    }
+
+    fn to_string(&self) -> String {
+        let receiver = match &self.receiver {
+            Object::Instance(instance) => format!("{} instance", instance.borrow().class.ident),
+            receiver => receiver.to_string(),
+        };
+        format!(
+            "<bound method {} of {}>",
+            self.method.declaration.ident.lexeme, receiver
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(BoundMethod {
+            receiver: self.receiver.clone(),
+            method: Function {
+                declaration: self.method.declaration.clone(),
+                closure: Rc::clone(&self.method.closure),
+            },
         })
     }
 }
 
 impl Object {
-    pub fn expect_number(self, line: &usize) -> anyhow::Result<f64> {
+    pub fn expect_number(self, line: &usize, column: &usize) -> anyhow::Result<f64> {
         if let Object::Number(n) = self {
             return Ok(n);
         }
 
-        bail!(runtime_error(line, "Expected number"))
+        bail!(runtime_error(line, column, "Expected number"))
     }
 
-    pub fn expect_string(self, line: &usize) -> anyhow::Result<String> {
+    pub fn expect_string(self, line: &usize, column: &usize) -> anyhow::Result<String> {
         if let Object::Str(s) = self {
             return Ok(s);
         }
 
-        bail!(runtime_error(line, "Expected string"))
+        bail!(runtime_error(line, column, "Expected string"))
     }
 
-    pub fn expect_boolean(self, line: &usize) -> anyhow::Result<bool> {
+    pub fn expect_boolean(self, line: &usize, column: &usize) -> anyhow::Result<bool> {
         if let Object::Boolean(b) = self {
             return Ok(b);
         }
 
-        bail!(runtime_error(line, "Expected boolean"))
+        bail!(runtime_error(line, column, "Expected boolean"))
+    }
+
+    // the position `self` indexes in a sequence of length `len`
+    pub fn expect_index(self, len: usize, line: &usize, column: &usize) -> anyhow::Result<usize> {
+        let index = self.expect_number(line, column)?;
+        if index.fract() != 0.0 {
+            bail!(runtime_error(
+                line,
+                column,
+                &format!("Expected an integer index, found {}", index)
+            ))
+        }
+
+        if index < 0.0 || index >= len as f64 {
+            bail!(runtime_error(
+                line,
+                column,
+                &format!("Index {} out of bounds for length {}", index, len)
+            ))
+        }
+
+        Ok(index as usize)
+    }
+
+    // `/.`, exact whatever the division mode
+    // `*`, which also repeats a string (`"ab" * 3`) or a list (`[0] * 3`). The result
+    // may hold at most `max_size` bytes of a string, or items of a list
+    pub fn mul(self, other: Object, max_size: usize) -> anyhow::Result<Object> {
+        match (self, other) {
+            (Object::Number(n1), Object::Number(n2)) => Ok(Object::Number(n1 * n2)),
+            (Object::Str(s), Object::Number(n)) | (Object::Number(n), Object::Str(s)) => {
+                let count = repetition_count(n, s.len(), max_size)?;
+                Ok(Object::Str(s.repeat(count)))
+            }
+            (Object::List(list), Object::Number(n)) | (Object::Number(n), Object::List(list)) => {
+                let items = list.borrow();
+                let count = repetition_count(n, items.len(), max_size)?;
+                // a nested list ends up shared between the copies, not duplicated
+                let repeated = (0..count).flat_map(|_| items.iter().cloned()).collect();
+                Ok(Object::List(Rc::new(RefCell::new(repeated))))
+            }
+            _ => bail!("Expected both operands to be numbers in multiplication operation"),
+        }
+    }
+
+    // `/`, which with `int_div` truncates toward zero between whole numbers like C
+    // integer division, leaving `/.` for exact division
+    pub fn div(self, other: Object, int_div: bool) -> anyhow::Result<Object> {
+        let whole = int_div
+            && matches!((&self, &other), (Object::Number(n1), Object::Number(n2))
+                if n1.fract() == 0.0 && n2.fract() == 0.0);
+        match self.true_div(other)? {
+            Object::Number(n) if whole => Ok(Object::Number(n.trunc())),
+            quotient => Ok(quotient),
+        }
+    }
+
+    pub fn true_div(self, other: Object) -> anyhow::Result<Object> {
+        match (self, other) {
+            (Object::Number(n1), Object::Number(n2)) => {
+                if n2 == 0.0 {
+                    bail!("Division by zero is not allowed")
+                }
+                Ok(Object::Number(n1 / n2))
+            }
+            _ => bail!("Expected both operands to be numbers in division operation"),
+        }
+    }
+
+    pub fn pow(self, other: Object) -> anyhow::Result<Object> {
+        match (self, other) {
+            (Object::Number(n1), Object::Number(n2)) => Ok(Object::Number(n1.powf(n2))),
+            _ => bail!("Expected both operands to be numbers in exponentiation operation"),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Str(_) => "string",
+            Object::Boolean(_) => "boolean",
+            Object::Number(_) => "number",
+            Object::Callable(_) => "function",
+            Object::Instance(_) => "instance",
+            Object::Module(_) => "module",
+            Object::Range(..) => "range",
+            Object::List(_) => "list",
+            Object::Map(_) => "map",
+            Object::Null => "null",
+        }
     }
 
     pub fn thrutiness(&self) -> bool {
@@ -163,20 +615,16 @@ impl Object {
             _ => true,
         }
     }
+
+    // the text the value displays as; `Display` is the default, without `strict_float`
+    pub fn display(&self, strict_float: bool) -> String {
+        format_value(self, strict_float, &mut HashSet::new())
+    }
 }
 
 impl Display for Object {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let msg = match self {
-            Object::Str(s) => s.to_string(),
-            Object::Boolean(b) => b.to_string(),
-            Object::Number(n) => n.to_string(),
-            Object::Null => "null".to_string(),
-            Object::Callable(callable) => callable.to_string(),
-            Object::Instance(instance) => format!("<{} instance>", instance.class.ident.clone()),
-        };
-
-        write!(f, "{}", msg)
+        write!(f, "{}", self.display(false))
     }
 }
 
@@ -197,29 +645,18 @@ impl ops::Add for Object {
     }
 }
 
-impl ops::Div for Object {
+impl ops::Rem for Object {
     type Output = anyhow::Result<Object>;
 
-    fn div(self, other: Object) -> Self::Output {
+    fn rem(self, other: Object) -> Self::Output {
         match (self, other) {
             (Object::Number(n1), Object::Number(n2)) => {
                 if n2 == 0.0 {
-                    bail!("Division by zero is not allowed")
+                    bail!("Modulo by zero is not allowed")
                 }
-                Ok(Object::Number(n1 / n2))
+                Ok(Object::Number(n1 % n2))
             }
-            _ => bail!("Expected both operands to be numbers in division operation"),
-        }
-    }
-}
-
-impl ops::Mul for Object {
-    type Output = anyhow::Result<Object>;
-
-    fn mul(self, other: Object) -> Self::Output {
-        match (self, other) {
-            (Object::Number(n1), Object::Number(n2)) => Ok(Object::Number(n1 * n2)),
-            _ => bail!("Expected both operands to be numbers in multiplication operation"),
+            _ => bail!("Expected both operands to be numbers in modulo operation"),
         }
     }
 }
@@ -253,12 +690,18 @@ impl PartialEq for Object {
             (Object::Boolean(a), Object::Boolean(b)) => a == b,
             (Object::Null, Object::Null) => true,
             (Object::Callable(_), Object::Callable(_)) => false,
+            (Object::Instance(a), Object::Instance(b)) => Rc::ptr_eq(a, b),
+            (Object::Module(a), Object::Module(b)) => Rc::ptr_eq(a, b),
+            (Object::Range(s1, e1), Object::Range(s2, e2)) => s1 == s2 && e1 == e2,
+            (Object::List(_), Object::List(_)) | (Object::Map(_), Object::Map(_)) => {
+                values_equal(self, other, &mut HashSet::new())
+            }
             _ => false,
         }
     }
 }
 
-impl Clone for Box<dyn Callable + Send + Sync + 'static> {
+impl Clone for Box<dyn Callable> {
     fn clone(&self) -> Self {
         self.clone_box()
     }
@@ -272,21 +715,17 @@ impl Clone for Object {
             Object::Boolean(b) => Object::Boolean(*b),
             Object::Null => Object::Null,
             Object::Callable(c) => Object::Callable(c.clone()),
-            Object::Instance(instance) => Object::Instance(instance.clone()),
+            Object::Instance(instance) => Object::Instance(Rc::clone(instance)),
+            Object::Module(module) => Object::Module(Rc::clone(module)),
+            Object::Range(start, end) => Object::Range(*start, *end),
+            Object::List(list) => Object::List(Rc::clone(list)),
+            Object::Map(map) => Object::Map(Rc::clone(map)),
         }
     }
 }
 
 impl Debug for Object {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let msg = match self {
-            Object::Str(s) => format!("{s}"),
-            Object::Number(n) => format!("{n}"),
-            Object::Null => format!("null"),
-            Object::Boolean(b) => format!("{b}"),
-            Object::Callable(c) => format!("{}", c.to_string()),
-            Object::Instance(instance) => format!("<{} instance>", instance.class.ident.clone()),
-        };
-        write!(f, "{msg}")
+        write!(f, "{}", self.display(false))
     }
 }
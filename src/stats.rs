@@ -0,0 +1,179 @@
+use std::fmt::Display;
+
+use crate::grammar::{Declaration, Expression, FnDecl, Statement};
+
+// Counts of what a program parsed into, for `--no-run` reports on large scripts.
+#[derive(Debug, Default)]
+pub struct ParseStats {
+    pub tokens: usize,
+    pub declarations: usize,
+    pub statements: usize,
+    pub expressions: usize,
+    // deepest chain of nested declarations, statements and expressions
+    pub max_depth: usize,
+}
+
+impl ParseStats {
+    pub fn collect(tokens: usize, declarations: &[Declaration]) -> Self {
+        let mut stats = Self {
+            tokens,
+            ..Self::default()
+        };
+        for decl in declarations {
+            stats.visit_declaration(decl, 1);
+        }
+        stats
+    }
+
+    fn enter(&mut self, depth: usize) {
+        self.max_depth = self.max_depth.max(depth);
+    }
+
+    fn visit_declaration(&mut self, decl: &Declaration, depth: usize) {
+        self.declarations += 1;
+        self.enter(depth);
+        match decl {
+            Declaration::StmtDecl(stmt_decl) => self.visit_statement(&stmt_decl.stmt, depth + 1),
+            Declaration::LetDecl(let_decl) => {
+                if let Some(init) = &let_decl.init {
+                    self.visit_expression(init, depth + 1);
+                }
+            }
+            Declaration::FnDecl(fn_decl) => self.visit_fn(fn_decl, depth + 1),
+            Declaration::ClassDecl(class_decl) => {
+                for method in &class_decl.methods {
+                    self.visit_fn(method, depth + 1);
+                }
+            }
+            Declaration::ExportDecl(export_decl) => {
+                self.visit_declaration(&export_decl.decl, depth + 1)
+            }
+        }
+    }
+
+    fn visit_fn(&mut self, fn_decl: &FnDecl, depth: usize) {
+        self.visit_statement(&fn_decl.body, depth);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement, depth: usize) {
+        self.statements += 1;
+        self.enter(depth);
+        match stmt {
+            Statement::ExprStmt(expr_stmt) => self.visit_expression(&expr_stmt.expr, depth + 1),
+            Statement::BlockStmt(block_stmt) => {
+                for decl in &block_stmt.stmts {
+                    self.visit_declaration(decl, depth + 1);
+                }
+            }
+            Statement::IfStmt(if_stmt) => {
+                self.visit_expression(&if_stmt.condition, depth + 1);
+                self.visit_statement(&if_stmt.if_branch, depth + 1);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    self.visit_statement(else_branch, depth + 1);
+                }
+            }
+            Statement::WhileStmt(while_stmt) => {
+                self.visit_expression(&while_stmt.condition, depth + 1);
+                self.visit_statement(&while_stmt.body, depth + 1);
+                if let Some(increment) = &while_stmt.increment {
+                    self.visit_expression(increment, depth + 1);
+                }
+            }
+            Statement::ReturnStmt(return_stmt) => {
+                if let Some(expr) = &return_stmt.expr {
+                    self.visit_expression(expr, depth + 1);
+                }
+            }
+            Statement::TryStmt(try_stmt) => {
+                self.visit_statement(&try_stmt.body, depth + 1);
+                if let Some(catch_body) = &try_stmt.catch_body {
+                    self.visit_statement(catch_body, depth + 1);
+                }
+                if let Some(finally_body) = &try_stmt.finally_body {
+                    self.visit_statement(finally_body, depth + 1);
+                }
+            }
+            Statement::BreakStmt(_) | Statement::ContinueStmt(_) | Statement::ImportStmt(_) => (),
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression, depth: usize) {
+        self.expressions += 1;
+        self.enter(depth);
+        let depth = depth + 1;
+        match expr {
+            Expression::Literal(_)
+            | Expression::Var(_)
+            | Expression::This(_)
+            | Expression::Super(_) => (),
+            Expression::Call(call) => {
+                self.visit_expression(&call.callee, depth);
+                for arg in &call.args {
+                    self.visit_expression(arg, depth);
+                }
+            }
+            Expression::Get(get) => self.visit_expression(&get.object, depth),
+            Expression::Index(index) => {
+                self.visit_expression(&index.object, depth);
+                self.visit_expression(&index.index, depth);
+            }
+            Expression::SetIndex(set) => {
+                self.visit_expression(&set.object, depth);
+                self.visit_expression(&set.index, depth);
+                self.visit_expression(&set.value, depth);
+            }
+            Expression::List(list) => {
+                for item in &list.items {
+                    self.visit_expression(item, depth);
+                }
+            }
+            Expression::Unary(unary) => self.visit_expression(&unary.expr, depth),
+            Expression::Set(set) => {
+                self.visit_expression(&set.object, depth);
+                self.visit_expression(&set.value, depth);
+            }
+            Expression::Logical(logical) => {
+                self.visit_expression(&logical.left, depth);
+                self.visit_expression(&logical.right, depth);
+            }
+            Expression::Binary(binary) => {
+                self.visit_expression(&binary.left, depth);
+                self.visit_expression(&binary.right, depth);
+            }
+            Expression::Comprehension(comp) => {
+                self.visit_expression(&comp.iterable, depth);
+                self.visit_expression(&comp.expr, depth);
+                if let Some(condition) = &comp.condition {
+                    self.visit_expression(condition, depth);
+                }
+            }
+            Expression::Range(range) => {
+                self.visit_expression(&range.left, depth);
+                self.visit_expression(&range.right, depth);
+            }
+            Expression::Grouping(expr) => self.visit_expression(expr, depth),
+            Expression::Lambda(lambda) => self.visit_fn(lambda, depth),
+            Expression::Object(object) => {
+                for field in &object.fields {
+                    self.visit_expression(&field.value, depth);
+                }
+            }
+            Expression::Map(map) => {
+                for entry in &map.entries {
+                    self.visit_expression(&entry.value, depth);
+                }
+            }
+            Expression::Assignment(assignment) => self.visit_expression(&assignment.expr, depth),
+        }
+    }
+}
+
+impl Display for ParseStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "tokens:       {}", self.tokens)?;
+        writeln!(f, "declarations: {}", self.declarations)?;
+        writeln!(f, "statements:   {}", self.statements)?;
+        writeln!(f, "expressions:  {}", self.expressions)?;
+        write!(f, "max depth:    {}", self.max_depth)
+    }
+}
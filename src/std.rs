@@ -1,13 +1,50 @@
-use crate::runtime::{Callable, Object};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{self, Write},
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::bail;
+
+use crate::{
+    error::{runtime_error, Signal},
+    lexer::Lexer,
+    parser::Parser,
+    resolver::Resolver,
+    runtime::{str_len, Callable, Object},
+};
+
+pub struct Builtin {
+    pub name: String,
+    pub arity: usize,
+    pub max_arity: usize,
+    pub description: String,
+}
+
+impl Builtin {
+    fn signature(&self) -> String {
+        if self.arity == self.max_arity {
+            format!("{}/{}", self.name, self.arity)
+        } else {
+            format!("{}/{}-{}", self.name, self.arity, self.max_arity)
+        }
+    }
+}
 
 pub struct Println {}
 impl Callable for Println {
     fn call(
         &mut self,
-        _: &mut crate::interpreter::Interpreter,
+        interp: &mut crate::interpreter::Interpreter,
         args: Vec<crate::runtime::Object>,
     ) -> anyhow::Result<crate::runtime::Object> {
-        println!("{}", args[0]);
+        // arity is checked by the caller, but a direct call must not be able to panic
+        let Some(value) = args.first() else {
+            bail!("println expects a value to print")
+        };
+        println!("{}", interp.show(value));
         return Ok(Object::Null);
     }
 
@@ -19,7 +56,1291 @@ impl Callable for Println {
         "<std fn println>".to_string()
     }
 
-    fn clone_box(&self) -> Box<dyn Callable + Send + Sync + 'static> {
+    fn clone_box(&self) -> Box<dyn Callable> {
         Box::new(Println {})
     }
 }
+
+pub struct Print {}
+impl Callable for Print {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let Some(value) = args.first() else {
+            bail!("print expects a value to print")
+        };
+        print!("{}", interp.show(value));
+        io::stdout().flush()?;
+        Ok(Object::Null)
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn print>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Print {})
+    }
+}
+
+pub struct Len {}
+impl Callable for Len {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let len = match &args[0] {
+            Object::Str(s) => str_len(s),
+            Object::List(list) => list.borrow().len(),
+            Object::Map(map) => map.borrow().len(),
+            obj => bail!("len is not supported for a {}", obj.type_name()),
+        };
+        Ok(Object::Number(len as f64))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn len>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Len {})
+    }
+}
+
+pub struct TypeOf {}
+impl Callable for TypeOf {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        Ok(Object::Str(args[0].type_name().to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn typeof>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(TypeOf {})
+    }
+}
+
+pub struct ToNumber {}
+impl Callable for ToNumber {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        match &args[0] {
+            Object::Number(n) => Ok(Object::Number(*n)),
+            // a string that isn't a number converts to null, so callers can check without catching
+            Object::Str(s) => Ok(s.trim().parse::<f64>().map_or(Object::Null, Object::Number)),
+            obj => bail!("to_number is not supported for a {}", obj.type_name()),
+        }
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn to_number>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(ToNumber {})
+    }
+}
+
+pub struct Stringify {}
+impl Callable for Stringify {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        Ok(Object::Str(interp.show(&args[0])))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn to_string>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Stringify {})
+    }
+}
+
+fn number_arg(name: &str, arg: &Object) -> anyhow::Result<f64> {
+    match arg {
+        Object::Number(n) => Ok(*n),
+        obj => bail!("{} expects a number, found a {}", name, obj.type_name()),
+    }
+}
+
+// math builtins map straight onto the `f64` method of the same name
+pub struct UnaryMath {
+    pub name: &'static str,
+    pub op: fn(f64) -> f64,
+}
+impl Callable for UnaryMath {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let n = number_arg(self.name, &args[0])?;
+        Ok(Object::Number((self.op)(n)))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        format!("<std fn {}>", self.name)
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(UnaryMath {
+            name: self.name,
+            op: self.op,
+        })
+    }
+}
+
+pub struct BinaryMath {
+    pub name: &'static str,
+    pub op: fn(f64, f64) -> f64,
+}
+impl Callable for BinaryMath {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let a = number_arg(self.name, &args[0])?;
+        let b = number_arg(self.name, &args[1])?;
+        Ok(Object::Number((self.op)(a, b)))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        format!("<std fn {}>", self.name)
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(BinaryMath {
+            name: self.name,
+            op: self.op,
+        })
+    }
+}
+
+// a sum or difference past the bound is clamped to it instead of growing further
+pub struct SatAdd {}
+impl Callable for SatAdd {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let a = number_arg("sat_add", &args[0])?;
+        let b = number_arg("sat_add", &args[1])?;
+        let max = number_arg("sat_add", &args[2])?;
+        Ok(Object::Number((a + b).min(max)))
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn sat_add>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(SatAdd {})
+    }
+}
+
+pub struct SatSub {}
+impl Callable for SatSub {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let a = number_arg("sat_sub", &args[0])?;
+        let b = number_arg("sat_sub", &args[1])?;
+        let min = number_arg("sat_sub", &args[2])?;
+        Ok(Object::Number((a - b).max(min)))
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn sat_sub>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(SatSub {})
+    }
+}
+
+pub struct Collect {}
+impl Callable for Collect {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        match &args[0] {
+            Object::List(list) => Ok(Object::List(Rc::clone(list))),
+            Object::Range(start, end) => {
+                let len = (end - start).ceil().max(0.0);
+                if len > interp.max_result_size as f64 {
+                    bail!("Collected range {}..{} is too large", start, end)
+                }
+
+                let items = (0..len as usize)
+                    .map(|i| Object::Number(start + i as f64))
+                    .collect();
+                Ok(Object::List(Rc::new(RefCell::new(items))))
+            }
+            obj => bail!("collect is not supported for a {}", obj.type_name()),
+        }
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn collect>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Collect {})
+    }
+}
+
+fn str_arg<'a>(name: &str, arg: &'a Object) -> anyhow::Result<&'a str> {
+    match arg {
+        Object::Str(s) => Ok(s),
+        obj => bail!("{} expects a string, found a {}", name, obj.type_name()),
+    }
+}
+
+pub struct UnaryStr {
+    pub name: &'static str,
+    pub op: fn(&str) -> String,
+}
+impl Callable for UnaryStr {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let s = str_arg(self.name, &args[0])?;
+        Ok(Object::Str((self.op)(s)))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        format!("<std fn {}>", self.name)
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(UnaryStr {
+            name: self.name,
+            op: self.op,
+        })
+    }
+}
+
+pub struct Split {}
+impl Callable for Split {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let s = str_arg("split", &args[0])?;
+        let sep = str_arg("split", &args[1])?;
+
+        // an empty separator splits between every character
+        let parts = if sep.is_empty() {
+            s.chars().map(|c| Object::Str(c.to_string())).collect()
+        } else {
+            s.split(sep).map(|p| Object::Str(p.to_string())).collect()
+        };
+        Ok(Object::List(Rc::new(RefCell::new(parts))))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn split>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Split {})
+    }
+}
+
+pub struct Contains {}
+impl Callable for Contains {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let s = str_arg("contains", &args[0])?;
+        let sub = str_arg("contains", &args[1])?;
+        Ok(Object::Boolean(s.contains(sub)))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn contains>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Contains {})
+    }
+}
+
+// Sorts a copy of a list, keeping equal elements in their original order. By default
+// the list must hold only numbers or only strings, strings comparing lexicographically.
+// With `numeric` set, strings are parsed as numbers, so "10" sorts after "2"; a string
+// that isn't a number is an error rather than being placed arbitrarily.
+pub struct Sort {}
+impl Callable for Sort {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let items = match &args[0] {
+            Object::List(list) => list.borrow().clone(),
+            obj => bail!("sort is not supported for a {}", obj.type_name()),
+        };
+        let numeric = args.get(1).is_some_and(|flag| flag.thrutiness())
+            || items.iter().all(|item| matches!(item, Object::Number(_)));
+
+        let sorted = if numeric {
+            let mut keyed = items
+                .into_iter()
+                .map(|item| {
+                    let key = match &item {
+                        Object::Number(n) => *n,
+                        Object::Str(s) => match s.trim().parse::<f64>() {
+                            Ok(n) => n,
+                            Err(_) => bail!("sort cannot compare \"{}\" as a number", s),
+                        },
+                        obj => bail!("sort cannot compare a {} as a number", obj.type_name()),
+                    };
+                    Ok((key, item))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            keyed.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+            keyed.into_iter().map(|(_, item)| item).collect()
+        } else if items.iter().all(|item| matches!(item, Object::Str(_))) {
+            let mut items = items;
+            // a string displays as itself, so this is a plain lexicographic comparison
+            items.sort_by_cached_key(|item| item.to_string());
+            items
+        } else {
+            bail!("sort expects a list of only numbers or only strings")
+        };
+
+        Ok(Object::List(Rc::new(RefCell::new(sorted))))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn max_arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn sort>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Sort {})
+    }
+}
+
+// the items of a list and the callable applied to them, shared by the folding builtins.
+// The callable's arity is checked up front, as builtins index their arguments directly
+fn fold_args(
+    name: &str,
+    args: &[Object],
+    takes: usize,
+) -> anyhow::Result<(Vec<Object>, Box<dyn Callable>)> {
+    let items = match &args[0] {
+        Object::List(list) => list.borrow().clone(),
+        obj => bail!("{} is not supported for a {}", name, obj.type_name()),
+    };
+    let Object::Callable(callable) = args[1].clone() else {
+        bail!(
+            "{} expects a callable, found a {}",
+            name,
+            args[1].type_name()
+        )
+    };
+    if !(callable.arity()..=callable.max_arity()).contains(&takes) {
+        bail!(
+            "{} expects a callable taking {} argument{}, found {}",
+            name,
+            takes,
+            if takes == 1 { "" } else { "s" },
+            callable.arity()
+        )
+    }
+    Ok((items, callable))
+}
+
+pub struct ReduceRight {}
+impl Callable for ReduceRight {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let (items, mut callable) = fold_args("reduce_right", &args, 2)?;
+        let mut acc = args[2].clone();
+        for item in items.into_iter().rev() {
+            acc = callable.call(interp, vec![acc, item])?;
+        }
+        Ok(acc)
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn reduce_right>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(ReduceRight {})
+    }
+}
+
+pub struct Scan {}
+impl Callable for Scan {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let (items, mut callable) = fold_args("scan", &args, 2)?;
+        let mut acc = args[2].clone();
+        let mut steps = Vec::with_capacity(items.len());
+        for item in items {
+            acc = callable.call(interp, vec![acc, item])?;
+            steps.push(acc.clone());
+        }
+        Ok(Object::List(Rc::new(RefCell::new(steps))))
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn scan>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Scan {})
+    }
+}
+
+pub struct GroupBy {}
+impl Callable for GroupBy {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let (items, mut callable) = fold_args("group_by", &args, 1)?;
+        let mut groups: HashMap<String, Object> = HashMap::new();
+        for item in items {
+            // maps are keyed by strings, so only keys with a plain text form are accepted
+            let key = match callable.call(interp, vec![item.clone()])? {
+                key @ (Object::Str(_) | Object::Number(_) | Object::Boolean(_)) => key.to_string(),
+                key => bail!("group_by cannot use a {} as a key", key.type_name()),
+            };
+            let group = groups
+                .entry(key)
+                .or_insert_with(|| Object::List(Rc::new(RefCell::new(Vec::new()))));
+            if let Object::List(group) = group {
+                group.borrow_mut().push(item);
+            }
+        }
+        Ok(Object::Map(Rc::new(RefCell::new(groups))))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn group_by>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(GroupBy {})
+    }
+}
+
+pub struct Unique {}
+impl Callable for Unique {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let items = match &args[0] {
+            Object::List(list) => list.borrow().clone(),
+            obj => bail!("unique is not supported for a {}", obj.type_name()),
+        };
+
+        // compared pairwise, since lists and maps have no hash to look them up by
+        let mut unique: Vec<Object> = Vec::with_capacity(items.len());
+        for item in items {
+            if !unique.contains(&item) {
+                unique.push(item);
+            }
+        }
+        Ok(Object::List(Rc::new(RefCell::new(unique))))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn unique>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Unique {})
+    }
+}
+
+// `flatten` concatenates one level of nested lists, `flatten_deep` every level
+pub struct Flatten {
+    pub deep: bool,
+}
+
+impl Flatten {
+    fn name(&self) -> &'static str {
+        if self.deep {
+            "flatten_deep"
+        } else {
+            "flatten"
+        }
+    }
+
+    // `open` holds the lists being flattened, so one containing itself is caught
+    // rather than recursed into forever. Only lists whose items are flattened in
+    // turn can recurse, a shallow flatten leaves those below the first level whole
+    fn flatten_into(
+        &self,
+        list: &Rc<RefCell<Vec<Object>>>,
+        depth: usize,
+        open: &mut Vec<Rc<RefCell<Vec<Object>>>>,
+        out: &mut Vec<Object>,
+    ) -> anyhow::Result<()> {
+        let recursing = self.deep || depth == 0;
+        if recursing && open.iter().any(|outer| Rc::ptr_eq(outer, list)) {
+            bail!("{} found a list that contains itself", self.name())
+        }
+
+        open.push(Rc::clone(list));
+        for item in list.borrow().iter() {
+            match item {
+                Object::List(inner) if depth == 0 || self.deep => {
+                    self.flatten_into(inner, depth + 1, open, out)?
+                }
+                item => out.push(item.clone()),
+            }
+        }
+        open.pop();
+        Ok(())
+    }
+}
+
+impl Callable for Flatten {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let Object::List(list) = &args[0] else {
+            bail!(
+                "{} is not supported for a {}",
+                self.name(),
+                args[0].type_name()
+            )
+        };
+
+        let mut flat = Vec::new();
+        self.flatten_into(list, 0, &mut Vec::new(), &mut flat)?;
+        Ok(Object::List(Rc::new(RefCell::new(flat))))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        format!("<std fn {}>", self.name())
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Flatten { deep: self.deep })
+    }
+}
+
+// the items of a list and a size of at least one, shared by `chunk` and `windows`
+fn sized_args(name: &str, args: &[Object]) -> anyhow::Result<(Vec<Object>, usize)> {
+    let items = match &args[0] {
+        Object::List(list) => list.borrow().clone(),
+        obj => bail!("{} is not supported for a {}", name, obj.type_name()),
+    };
+    let size = number_arg(name, &args[1])?;
+    if size < 1.0 || size.fract() != 0.0 {
+        bail!(
+            "{} expects a whole size of at least 1, found {}",
+            name,
+            size
+        )
+    }
+    Ok((items, size as usize))
+}
+
+fn list_of_lists<'a>(lists: impl Iterator<Item = &'a [Object]>) -> Object {
+    let lists = lists
+        .map(|list| Object::List(Rc::new(RefCell::new(list.to_vec()))))
+        .collect();
+    Object::List(Rc::new(RefCell::new(lists)))
+}
+
+pub struct Chunk {}
+impl Callable for Chunk {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let (items, size) = sized_args("chunk", &args)?;
+        Ok(list_of_lists(items.chunks(size)))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn chunk>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Chunk {})
+    }
+}
+
+pub struct Windows {}
+impl Callable for Windows {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let (items, size) = sized_args("windows", &args)?;
+        Ok(list_of_lists(items.windows(size)))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn windows>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Windows {})
+    }
+}
+
+pub struct Memoize {}
+impl Callable for Memoize {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let Object::Callable(inner) = args[0].clone() else {
+            bail!(
+                "memoize expects a callable, found a {}",
+                args[0].type_name()
+            )
+        };
+
+        Ok(Object::Callable(Box::new(Memoized {
+            inner,
+            cache: Rc::new(RefCell::new(HashMap::new())),
+        })))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn memoize>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Memoize {})
+    }
+}
+
+// A callable returned by `memoize`, running the wrapped one once per set of arguments.
+// Callables are cloned whenever they are read, so every copy shares the one cache
+pub struct Memoized {
+    inner: Box<dyn Callable>,
+    cache: Rc<RefCell<HashMap<String, Object>>>,
+}
+
+impl Memoized {
+    // strings are quoted so "1" and 1 stay distinct keys
+    fn key(args: &[Object]) -> anyhow::Result<String> {
+        let keys = args
+            .iter()
+            .map(|arg| match arg {
+                Object::Str(s) => Ok(format!("{:?}", s)),
+                Object::Number(_) | Object::Boolean(_) | Object::Null => Ok(arg.to_string()),
+                obj => bail!(
+                    "memoized functions cannot take a {} argument",
+                    obj.type_name()
+                ),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(keys.join(", "))
+    }
+}
+
+impl Callable for Memoized {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let key = Self::key(&args)?;
+        if let Some(value) = self.cache.borrow().get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = self.inner.call(interp, args)?;
+        self.cache.borrow_mut().insert(key, value.clone());
+        Ok(value)
+    }
+
+    fn arity(&self) -> usize {
+        self.inner.arity()
+    }
+
+    fn max_arity(&self) -> usize {
+        self.inner.max_arity()
+    }
+
+    fn to_string(&self) -> String {
+        format!("<memoized {}>", self.inner.to_string())
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Memoized {
+            inner: self.inner.clone_box(),
+            cache: Rc::clone(&self.cache),
+        })
+    }
+}
+
+pub struct Input {}
+impl Callable for Input {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        if let Some(prompt) = args.first() {
+            print!("{}", prompt);
+            io::stdout().flush()?;
+        }
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Ok(Object::Null);
+        }
+
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        Ok(Object::Str(line.to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn max_arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn input>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Input {})
+    }
+}
+
+pub struct Clock {}
+impl Callable for Clock {
+    fn call(
+        &mut self,
+        _: &mut crate::interpreter::Interpreter,
+        _: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let elapsed = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        Ok(Object::Number(elapsed.as_secs_f64()))
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn clock>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Clock {})
+    }
+}
+
+// advances the interpreter's xorshift64 state, seeding it from the clock on first use
+fn next_random(interp: &mut crate::interpreter::Interpreter) -> u64 {
+    let mut x = interp.random_state;
+    if x == 0 {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        x = scramble(nanos);
+    }
+
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    interp.random_state = x;
+    x
+}
+
+// splitmix64, so that nearby seeds start from unrelated, never zero, states
+fn scramble(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    (z ^ (z >> 31)).max(1)
+}
+
+pub struct Random {}
+impl Callable for Random {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        _: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        // the top 53 bits fill an f64 mantissa exactly, keeping the result below 1
+        let n = (next_random(interp) >> 11) as f64 / (1u64 << 53) as f64;
+        Ok(Object::Number(n))
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn random>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Random {})
+    }
+}
+
+pub struct RandomInt {}
+impl Callable for RandomInt {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let lo = number_arg("random_int", &args[0])?;
+        let hi = number_arg("random_int", &args[1])?;
+        if lo.fract() != 0.0 || hi.fract() != 0.0 {
+            bail!("random_int expects integer bounds, found {} and {}", lo, hi)
+        }
+        if lo > hi {
+            bail!("random_int expects lo <= hi, found {} > {}", lo, hi)
+        }
+
+        let span = ((hi - lo) as u64).saturating_add(1);
+        Ok(Object::Number(lo + (next_random(interp) % span) as f64))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn random_int>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(RandomInt {})
+    }
+}
+
+pub struct Seed {}
+impl Callable for Seed {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let seed = number_arg("seed", &args[0])?;
+        interp.random_state = scramble(seed.to_bits());
+        Ok(Object::Null)
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn seed>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Seed {})
+    }
+}
+
+pub struct Breakpoint {}
+impl Callable for Breakpoint {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        _: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        if !interp.debug {
+            return Ok(Object::Null);
+        }
+
+        println!("breakpoint: 'env' lists bindings, 'c' resumes, anything else is executed");
+        loop {
+            print!("(breakpoint) ");
+            io::stdout().flush()?;
+
+            let mut line = String::new();
+            if io::stdin().read_line(&mut line)? == 0 {
+                break;
+            }
+
+            match line.trim() {
+                "c" | "continue" => break,
+                "env" => print_scopes(&interp.current),
+                "" => (),
+                source => {
+                    let snapshot = RefCell::borrow(&interp.current).snapshot();
+                    let scopes = interp.local_scopes();
+                    let result = Lexer::new(source.to_string())
+                        .tokenize()
+                        .and_then(|tokens| Parser::new(tokens).parse())
+                        .and_then(|mut decls| {
+                            Resolver::within(scopes).resolve(&mut decls)?;
+                            interp.interpret(decls)
+                        });
+                    if let Err(e) = result {
+                        println!("{e}");
+                    }
+
+                    let defined = RefCell::borrow(&interp.current).diff(&snapshot);
+                    if !defined.is_empty() {
+                        println!("defined: {}", defined.join(", "));
+                    }
+                }
+            }
+        }
+
+        Ok(Object::Null)
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn breakpoint>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Breakpoint {})
+    }
+}
+
+pub struct Assert {}
+impl Callable for Assert {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        if args[0].thrutiness() {
+            return Ok(Object::Null);
+        }
+
+        let (line, column) = interp.call_site;
+        let msg = match args.get(1) {
+            Some(msg) => format!("Assertion failed: {}", msg),
+            None => "Assertion failed".to_string(),
+        };
+        bail!(runtime_error(&line, &column, &msg))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn max_arity(&self) -> usize {
+        2
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn assert>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Assert {})
+    }
+}
+
+// names only, printing values could run arbitrary code through their display
+pub struct DebugEnv {}
+impl Callable for DebugEnv {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        _: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        print_scopes(&interp.current);
+        Ok(Object::Null)
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn debug_env>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(DebugEnv {})
+    }
+}
+
+pub struct AssertThrows {}
+impl Callable for AssertThrows {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let Object::Callable(mut callable) = args[0].clone() else {
+            bail!("assert_throws expects a callable")
+        };
+        if callable.arity() != 0 {
+            bail!(
+                "assert_throws expects a callable taking no arguments, found {}",
+                callable.arity()
+            )
+        }
+
+        match callable.call(interp, Vec::new()) {
+            // control flow is not a failure of the callable, let it reach its owner
+            Err(e) if e.is::<Signal>() => Err(e),
+            Err(e) => Ok(Object::Str(e.to_string().trim().to_string())),
+            Ok(_) => bail!(
+                "assertion failed: {} completed without raising an error",
+                callable.to_string()
+            ),
+        }
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn assert_throws>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(AssertThrows {})
+    }
+}
+
+pub struct Eval {}
+impl Callable for Eval {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        if interp.sandbox {
+            bail!("eval is disabled in sandbox mode")
+        }
+
+        let Object::Str(source) = &args[0] else {
+            bail!("eval expects a string of source code")
+        };
+
+        // a lone expression such as "1 + 2" needs no trailing ';'
+        let mut source = source.trim().to_string();
+        if !source.ends_with(';') && !source.ends_with('}') {
+            source.push(';');
+        }
+
+        let tokens = Lexer::new(source).tokenize()?;
+        let mut decls = Parser::new(tokens).parse()?;
+        Resolver::within(interp.local_scopes()).resolve(&mut decls)?;
+        interp.interpret_value(decls)
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn eval>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Eval {})
+    }
+}
+
+pub struct Help {}
+impl Callable for Help {
+    fn call(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        args: Vec<crate::runtime::Object>,
+    ) -> anyhow::Result<crate::runtime::Object> {
+        let Some(name) = args.first() else {
+            for builtin in &interp.builtins {
+                println!("  {:<16} {}", builtin.signature(), builtin.description);
+            }
+            return Ok(Object::Null);
+        };
+
+        let Object::Str(name) = name else {
+            bail!("Expected the name of a builtin as a string")
+        };
+
+        match interp.builtins.iter().find(|b| &b.name == name) {
+            Some(builtin) => println!("{}: {}", builtin.signature(), builtin.description),
+            None => bail!("No builtin named '{}'", name),
+        }
+
+        Ok(Object::Null)
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn max_arity(&self) -> usize {
+        1
+    }
+
+    fn to_string(&self) -> String {
+        "<std fn help>".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Callable> {
+        Box::new(Help {})
+    }
+}
+
+// names with an `@`, a `for` loop's end bound and the counter of `for _`, are bindings the parser
+// made up and can't be written in source, so they are left out
+fn print_scopes(env: &Rc<RefCell<crate::env::Environment>>) {
+    let mut scope = Some(Rc::clone(env));
+    let mut depth = 0;
+    while let Some(env) = scope {
+        let env = RefCell::borrow(&env);
+        let mut names = env.names();
+        names.retain(|name| !name.contains('@'));
+        println!("  [{}] {}", depth, names.join(", "));
+        scope = env.enclosing();
+        depth += 1;
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 
 use once_cell::sync::Lazy;
 
@@ -7,14 +7,38 @@ pub struct Token {
     pub lexeme: String,
     pub ty: TokenType,
     pub line: usize,
+    pub column: usize,
 }
 
 impl Token {
-    pub fn new(lexeme: String, ty: TokenType, line: usize) -> Self {
-        Self { lexeme, ty, line }
+    pub fn new(lexeme: String, ty: TokenType, line: usize, column: usize) -> Self {
+        Self {
+            lexeme,
+            ty,
+            line,
+            column,
+        }
     }
 }
 
+// Source the parser never sees, kept so a formatter can reproduce blank lines and comments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+    // index of the token that follows, equal to the token count for trailing trivia
+    pub before: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriviaKind {
+    // a run of spaces, tabs and newlines, so blank lines show up as two or more newlines
+    Whitespace,
+    Comment,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     //single char Tokens
@@ -22,13 +46,18 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
     Dot,
     Minus,
     Plus,
     Semicolon,
     Slash,
     Star,
+    StarStar,
+    Percent,
 
     //single or double char tokens
     Bang,
@@ -40,6 +69,13 @@ pub enum TokenType {
     Less,
     LessEqual,
     DotDot,
+    Ellipsis,
+    Pipe,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    SlashDot,
 
     // literals
     Identifier,
@@ -48,12 +84,20 @@ pub enum TokenType {
 
     //keywords
     And,
+    As,
+    Break,
+    Catch,
     Class,
+    Const,
+    Continue,
     Else,
+    Export,
     False,
+    Finally,
     Fn,
     For,
     If,
+    Import,
     In,
     Null,
     Or,
@@ -61,17 +105,76 @@ pub enum TokenType {
     Super,
     This,
     True,
+    Try,
     Let,
     While,
 }
 
+impl Display for TokenType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            TokenType::LeftParen => "(",
+            TokenType::RightParen => ")",
+            TokenType::LeftBrace => "{",
+            TokenType::RightBrace => "}",
+            TokenType::LeftBracket => "[",
+            TokenType::RightBracket => "]",
+            TokenType::Comma => ",",
+            TokenType::Colon => ":",
+            TokenType::Dot => ".",
+            TokenType::Minus => "-",
+            TokenType::Plus => "+",
+            TokenType::Semicolon => ";",
+            TokenType::Slash => "/",
+            TokenType::Star => "*",
+            TokenType::StarStar => "**",
+            TokenType::Percent => "%",
+            TokenType::Bang => "!",
+            TokenType::BangEqual => "!=",
+            TokenType::Equal => "=",
+            TokenType::EqualEqual => "==",
+            TokenType::Greater => ">",
+            TokenType::GreaterEqual => ">=",
+            TokenType::Less => "<",
+            TokenType::LessEqual => "<=",
+            TokenType::DotDot => "..",
+            TokenType::Ellipsis => "...",
+            TokenType::Pipe => "|>",
+            TokenType::PlusEqual => "+=",
+            TokenType::MinusEqual => "-=",
+            TokenType::StarEqual => "*=",
+            TokenType::SlashEqual => "/=",
+            TokenType::SlashDot => "/.",
+            TokenType::Identifier => return write!(f, "identifier"),
+            TokenType::String(_) => return write!(f, "string literal"),
+            TokenType::Number(_) => return write!(f, "number literal"),
+            keyword => {
+                let name = KEYWORDS
+                    .iter()
+                    .find(|(_, ty)| *ty == keyword)
+                    .map(|(name, _)| name.as_str())
+                    .unwrap_or("?");
+                return write!(f, "keyword `{}`", name);
+            }
+        };
+
+        write!(f, "`{}`", symbol)
+    }
+}
+
 pub static KEYWORDS: Lazy<HashMap<String, TokenType>> = Lazy::new(|| {
     let mut keywords = HashMap::new();
     keywords.insert("let".to_string(), TokenType::Let);
+    keywords.insert("const".to_string(), TokenType::Const);
     keywords.insert("fn".to_string(), TokenType::Fn);
     keywords.insert("while".to_string(), TokenType::While);
     keywords.insert("for".to_string(), TokenType::For);
     keywords.insert("in".to_string(), TokenType::In);
+    keywords.insert("break".to_string(), TokenType::Break);
+    keywords.insert("continue".to_string(), TokenType::Continue);
+    keywords.insert("import".to_string(), TokenType::Import);
+    keywords.insert("as".to_string(), TokenType::As);
+    keywords.insert("export".to_string(), TokenType::Export);
     keywords.insert("and".to_string(), TokenType::And);
     keywords.insert("or".to_string(), TokenType::Or);
     keywords.insert("if".to_string(), TokenType::If);
@@ -83,5 +186,8 @@ pub static KEYWORDS: Lazy<HashMap<String, TokenType>> = Lazy::new(|| {
     keywords.insert("this".to_string(), TokenType::This);
     keywords.insert("super".to_string(), TokenType::Super);
     keywords.insert("class".to_string(), TokenType::Class);
+    keywords.insert("try".to_string(), TokenType::Try);
+    keywords.insert("catch".to_string(), TokenType::Catch);
+    keywords.insert("finally".to_string(), TokenType::Finally);
     keywords
 });
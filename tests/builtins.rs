@@ -0,0 +1,330 @@
+mod common;
+
+use common::{error, eval_in, value};
+use interp::interpreter::Interpreter;
+
+#[test]
+fn assert_throws_returns_the_error_message() {
+    let message = value("assert_throws(fn () { return 1 / \"a\"; })");
+    assert!(message.contains("division"), "{message}");
+}
+
+#[test]
+fn assert_throws_fails_when_nothing_is_raised() {
+    let message = error("fn fine() { return 1; } assert_throws(fine)");
+    assert!(
+        message.contains("fine> completed without raising an error"),
+        "{message}"
+    );
+}
+
+#[test]
+fn assert_throws_rejects_callables_taking_arguments() {
+    let message = error("assert_throws(len)");
+    assert!(
+        message.contains("taking no arguments, found 1"),
+        "{message}"
+    );
+    let message = error("fn f(x) { return x; } assert_throws(f)");
+    assert!(
+        message.contains("taking no arguments, found 1"),
+        "{message}"
+    );
+}
+
+#[test]
+fn eval_returns_the_final_expression() {
+    assert_eq!(value("eval(\"1 + 2\")"), "3");
+    assert_eq!(value("eval(\"let x = 2; x * 10\")"), "20");
+}
+
+#[test]
+fn eval_sees_and_defines_bindings_in_the_calling_scope() {
+    assert_eq!(value("let x = 4; eval(\"x * x\")"), "16");
+    assert_eq!(value("eval(\"let y = 5;\"); y"), "5");
+}
+
+#[test]
+fn eval_reports_errors_in_the_source() {
+    assert!(error("eval(\"1 +\")").contains("SyntaxError"));
+    assert!(error("eval(\"missing\")").contains("Undefined variable 'missing'"));
+}
+
+#[test]
+fn eval_is_disabled_in_sandbox_mode() {
+    let mut interp = Interpreter::new();
+    interp.sandbox = true;
+    let error = eval_in(&mut interp, "eval(\"1\")").err().unwrap();
+    assert!(error.to_string().contains("disabled in sandbox mode"));
+}
+
+#[test]
+fn collect_materializes_a_range() {
+    assert_eq!(value("collect(0..4)"), "[0, 1, 2, 3]");
+    assert_eq!(value("collect(-1..1)"), "[-1, 0]");
+    assert_eq!(value("collect(3..3)"), "[]");
+}
+
+#[test]
+fn collect_returns_a_list_unchanged() {
+    assert_eq!(value("collect([1, 2])"), "[1, 2]");
+    assert_eq!(value("let l = [1]; collect(l) == l"), "true");
+}
+
+#[test]
+fn collect_rejects_huge_ranges() {
+    assert!(error("collect(0..1e12)").contains("is too large"));
+}
+
+#[test]
+fn sort_compares_strings_lexicographically_by_default() {
+    assert_eq!(value("sort([\"10\", \"2\", \"1\"])"), "[1, 10, 2]");
+    assert_eq!(value("sort([\"b\", \"a\", \"c\"])"), "[a, b, c]");
+}
+
+#[test]
+fn numeric_sort_parses_strings_as_numbers() {
+    assert_eq!(value("sort([\"10\", \"2\", \"1\"], true)"), "[1, 2, 10]");
+    assert_eq!(value("sort([3, \"2.5\", 1], true)"), "[1, 2.5, 3]");
+}
+
+#[test]
+fn numeric_sort_rejects_non_numeric_strings() {
+    let message = error("sort([\"1\", \"two\"], true)");
+    assert!(
+        message.contains("cannot compare \"two\" as a number"),
+        "{message}"
+    );
+}
+
+#[test]
+fn sort_is_stable_for_equal_keys() {
+    assert_eq!(value("sort([\"1.0\", 1, \"1\"], true)"), "[1.0, 1, 1]");
+}
+
+#[test]
+fn saturating_arithmetic_within_the_bounds() {
+    assert_eq!(value("sat_add(1, 2, 10)"), "3");
+    assert_eq!(value("sat_sub(5, 2, 0)"), "3");
+    assert_eq!(value("sat_add(8, 2, 10)"), "10");
+}
+
+#[test]
+fn saturating_arithmetic_clamps_beyond_the_bounds() {
+    assert_eq!(value("sat_add(8, 5, 10)"), "10");
+    assert_eq!(value("sat_sub(1, 5, 0)"), "0");
+    assert_eq!(value("sat_add(1e308, 1e308, 100)"), "100");
+}
+
+#[test]
+fn saturating_arithmetic_expects_numbers() {
+    assert!(error("sat_add(1, \"a\", 3)").contains("sat_add expects a number, found a string"));
+}
+
+#[test]
+fn eval_is_resolved_against_the_calling_scope() {
+    let source = "
+        let x = 1;
+        fn f() { let x = 2; return eval(\"x\"); }
+        f()";
+    assert_eq!(value(source), "2");
+    let source = "
+        fn g() { let y = 2; eval(\"y = y + 5\"); return y; }
+        g()";
+    assert_eq!(value(source), "7");
+}
+
+#[test]
+fn eval_reports_resolution_errors() {
+    let message = error("eval(\"return 1\")");
+    assert!(
+        message.contains("Can't return from outside a function"),
+        "{message}"
+    );
+}
+
+#[test]
+fn reduce_right_folds_from_the_end() {
+    let source = "reduce_right([\"a\", \"b\", \"c\"], fn (acc, s) { return acc + s; }, \">\")";
+    assert_eq!(value(source), ">cba");
+    assert_eq!(value("reduce_right([], fn (acc, x) { return x; }, 7)"), "7");
+}
+
+#[test]
+fn scan_returns_every_intermediate_value() {
+    let source = "scan([1, 2, 3, 4], fn (acc, x) { return acc + x; }, 0)";
+    assert_eq!(value(source), "[1, 3, 6, 10]");
+    assert_eq!(value("scan([], fn (acc, x) { return x; }, 0)"), "[]");
+}
+
+#[test]
+fn folds_reject_callables_of_the_wrong_arity() {
+    let message = error("scan([1], len, 0)");
+    assert!(
+        message.contains("scan expects a callable taking 2 arguments, found 1"),
+        "{message}"
+    );
+    let message = error("reduce_right([1], fn () { return 1; }, 0)");
+    assert!(
+        message.contains("reduce_right expects a callable taking 2 arguments, found 0"),
+        "{message}"
+    );
+}
+
+#[test]
+fn group_by_collects_items_under_their_key() {
+    let source = "group_by([1, 2, 3, 4, 5], fn (x) { return x % 2 == 0; })";
+    assert_eq!(value(source), "{\"false\": [1, 3, 5], \"true\": [2, 4]}");
+    let source = "group_by([\"apple\", \"avocado\", \"banana\"], fn (s) { return s[0]; })";
+    assert_eq!(value(source), "{\"a\": [apple, avocado], \"b\": [banana]}");
+    assert_eq!(value("group_by([], fn (x) { return x; })"), "{}");
+}
+
+#[test]
+fn group_by_rejects_callables_of_the_wrong_arity() {
+    let message = error("group_by([1], fn (a, b) { return a; })");
+    assert!(
+        message.contains("group_by expects a callable taking 1 argument, found 2"),
+        "{message}"
+    );
+    let message = error("group_by([1], fn () { return 1; })");
+    assert!(
+        message.contains("group_by expects a callable taking 1 argument, found 0"),
+        "{message}"
+    );
+}
+
+#[test]
+fn unique_keeps_the_first_of_each_item() {
+    assert_eq!(value("unique([3, 1, 3, 2, 1])"), "[3, 1, 2]");
+    assert_eq!(value("unique([\"a\", [1], \"a\", [1]])"), "[a, [1]]");
+}
+
+#[test]
+fn unique_leaves_unique_and_empty_lists_alone() {
+    assert_eq!(value("unique([1, 2, 3])"), "[1, 2, 3]");
+    assert_eq!(value("unique([])"), "[]");
+}
+
+#[test]
+fn flatten_concatenates_one_level() {
+    assert_eq!(value("flatten([[1, 2], [3], []])"), "[1, 2, 3]");
+    assert_eq!(value("flatten([1, [2, [3]], \"a\"])"), "[1, 2, [3], a]");
+}
+
+#[test]
+fn flatten_deep_flattens_every_level() {
+    assert_eq!(
+        value("flatten_deep([1, [2, [3, [4]]], []])"),
+        "[1, 2, 3, 4]"
+    );
+}
+
+#[test]
+fn flatten_keeps_a_list_containing_itself_below_the_first_level() {
+    let source = "let a = [1, 2]; a[1] = a; let flat = flatten(a); flat[2] == a";
+    assert_eq!(value(source), "true");
+    let source = "let a = [1, 2]; a[1] = a; len(flatten(a))";
+    assert_eq!(value(source), "3");
+}
+
+#[test]
+fn flatten_deep_rejects_a_list_containing_itself() {
+    let message = error("let a = [1, 2]; a[1] = a; flatten_deep(a)");
+    assert!(
+        message.contains("flatten_deep found a list that contains itself"),
+        "{message}"
+    );
+}
+
+#[test]
+fn chunk_splits_evenly_and_unevenly() {
+    assert_eq!(value("chunk([1, 2, 3, 4], 2)"), "[[1, 2], [3, 4]]");
+    assert_eq!(value("chunk([1, 2, 3, 4, 5], 2)"), "[[1, 2], [3, 4], [5]]");
+    assert_eq!(value("chunk([1, 2], 5)"), "[[1, 2]]");
+}
+
+#[test]
+fn windows_overlap() {
+    assert_eq!(
+        value("windows([1, 2, 3, 4], 2)"),
+        "[[1, 2], [2, 3], [3, 4]]"
+    );
+    assert_eq!(value("windows([1, 2], 3)"), "[]");
+}
+
+#[test]
+fn chunk_and_windows_need_a_size_of_at_least_one() {
+    assert!(error("chunk([1], 0)").contains("chunk expects a whole size of at least 1, found 0"));
+    assert!(error("windows([1], 1.5)").contains("windows expects a whole size of at least 1"));
+}
+
+#[test]
+fn memoize_calls_the_function_once_per_argument() {
+    let source = "
+        let calls = 0;
+        fn square(x) { calls = calls + 1; return x * x; }
+        let fast = memoize(square);
+        let results = [fast(3), fast(3), fast(4), fast(3)];
+        [calls, results]";
+    assert_eq!(value(source), "[2, [9, 9, 16, 9]]");
+}
+
+#[test]
+fn memoized_copies_share_their_cache() {
+    let source = "
+        let calls = 0;
+        fn f(x) { calls = calls + 1; return x; }
+        let a = memoize(f);
+        let b = a;
+        a(1);
+        b(1);
+        calls";
+    assert_eq!(value(source), "1");
+}
+
+#[test]
+fn seed_makes_random_repeatable() {
+    assert_eq!(
+        value("seed(1); let a = random(); seed(1); a == random()"),
+        "true"
+    );
+    assert_eq!(
+        value("seed(7); let a = random_int(0, 1000); seed(7); a == random_int(0, 1000)"),
+        "true"
+    );
+}
+
+#[test]
+fn random_stays_in_the_unit_interval() {
+    let source = "
+        let ok = true;
+        for _ in 0..1000 {
+            let n = random();
+            if (n < 0 or n >= 1) { ok = false; }
+        }
+        ok";
+    assert_eq!(value(source), "true");
+}
+
+#[test]
+fn random_int_includes_both_bounds() {
+    assert_eq!(value("random_int(3, 3)"), "3");
+    let source = "
+        let ok = true;
+        for _ in 0..1000 {
+            let n = random_int(-2, 2);
+            if (n < -2 or n > 2 or n != floor(n)) { ok = false; }
+        }
+        ok";
+    assert_eq!(value(source), "true");
+}
+
+#[test]
+fn random_int_rejects_bad_bounds() {
+    assert!(error("random_int(5, 1)").contains("random_int expects lo <= hi, found 5 > 1"));
+    assert!(
+        error("random_int(0.5, 2)").contains("random_int expects integer bounds, found 0.5 and 2")
+    );
+    assert!(error("random_int(0, \"a\")").contains("random_int expects a number, found a string"));
+}
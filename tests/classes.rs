@@ -0,0 +1,66 @@
+mod common;
+
+use common::value;
+
+#[test]
+fn bound_method_names_its_receiver() {
+    let source = "class Bar { fn foo() { return 1; } } Bar().foo";
+    assert_eq!(value(source), "<bound method foo of Bar instance>");
+}
+
+#[test]
+fn plain_function_is_not_shown_as_bound() {
+    assert_eq!(value("fn foo() { return 1; } foo"), "<user fn foo>");
+    assert_eq!(value("class Bar {} Bar"), "<class Bar>");
+}
+
+#[test]
+fn bound_method_remembers_its_receiver() {
+    let source = "
+        class Counter {
+            fn init(n) { this.n = n; }
+            fn get() { return this.n; }
+        }
+        let get = Counter(7).get;
+        get()";
+    assert_eq!(value(source), "7");
+}
+
+#[test]
+fn methods_update_their_instance() {
+    let source = "
+        class Counter {
+            fn init() { this.count = 0; }
+            fn increment() { this.count = this.count + 1; return this; }
+        }
+        let c = Counter();
+        c.increment();
+        c.increment().increment();
+        c.count";
+    assert_eq!(value(source), "3");
+}
+
+#[test]
+fn fields_shadow_methods() {
+    let source = "
+        class A { fn name() { return \"method\"; } }
+        let a = A();
+        a.name = \"field\";
+        a.name";
+    assert_eq!(value(source), "field");
+}
+
+#[test]
+fn object_literal_fields_read_and_print() {
+    assert_eq!(value("let p = {x = 1, y = 2}; p.x + p.y"), "3");
+    assert_eq!(value("let p = {y = \"b\", x = 1}; p"), "{x = 1, y = b}");
+}
+
+#[test]
+fn object_literal_containing_itself_prints() {
+    assert_eq!(value("let o = {x = 1}; o.x = o; o"), "{x = {...}}");
+    assert_eq!(
+        value("let l = [1]; let o = {items = l}; l[0] = o; o"),
+        "{items = [{...}]}"
+    );
+}
@@ -0,0 +1,247 @@
+mod common;
+
+use common::{binary, script, script_with_input};
+
+#[test]
+fn trace_shows_calls_indented_by_depth() {
+    let source = "
+        fn fact(n) {
+            if (n <= 1) { return 1; }
+            return n * fact(n - 1);
+        }
+        fact(3);";
+    let output = script(source, &["--trace"]);
+    let trace = output.stderr.lines().collect::<Vec<_>>();
+    assert_eq!(
+        trace,
+        [
+            "-> fact(3)",
+            "  -> fact(2)",
+            "    -> fact(1)",
+            "    <- fact = 1",
+            "  <- fact = 2",
+            "<- fact = 6",
+        ]
+    );
+}
+
+#[test]
+fn no_trace_without_the_flag() {
+    let output = script("fn f() { return 1; } f();", &[]);
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn breakpoint_inspects_and_changes_the_environment() {
+    let source = "
+        let outer = 1;
+        {
+            let inner = 2;
+            breakpoint();
+            println(inner);
+        }";
+    let output = script_with_input(source, &["--debug"], "env\ninner = inner + outer;\nc\n");
+    assert!(output.stdout.contains("[0] inner"), "{}", output.stdout);
+    assert!(output.stdout.contains("outer"));
+    assert!(output.stdout.ends_with("3\n"), "{}", output.stdout);
+}
+
+#[test]
+fn breakpoint_outside_debug_mode_does_nothing() {
+    let source = "breakpoint(); println(1);";
+    let output = script_with_input(source, &[], "env\nc\n");
+    assert_eq!(output.stdout, "1\n");
+}
+
+#[test]
+fn whole_results_print_without_a_decimal_point() {
+    let output = script("println(6 / 2); println(6.0 / 4.0);", &[]);
+    assert_eq!(output.stdout, "3\n1.5\n");
+}
+
+#[test]
+fn strict_float_always_prints_a_decimal_point() {
+    let output = script("println(6 / 2); println(6.0 / 4.0);", &["--strict-float"]);
+    assert_eq!(output.stdout, "3.0\n1.5\n");
+}
+
+#[test]
+fn help_lists_builtins_with_their_arity() {
+    let output = script("help();", &[]);
+    assert!(output.stdout.contains("  println/1 "), "{}", output.stdout);
+    assert!(output.stdout.contains("  help/0-1 "), "{}", output.stdout);
+}
+
+#[test]
+fn help_describes_a_single_builtin() {
+    let output = script("help(\"len\");", &[]);
+    assert_eq!(
+        output.stdout,
+        "len/1: Returns the length of a string, list or map\n"
+    );
+    let output = script("help(\"nope\");", &[]);
+    assert!(output.stdout.contains("No builtin named 'nope'"));
+}
+
+#[test]
+fn no_run_reports_parse_stats_without_running() {
+    let output = script("println(\"ran\");\nlet x = 1 + 2;", &["--no-run"]);
+    assert!(!output.stdout.contains("ran"), "{}", output.stdout);
+    assert!(
+        output.stdout.contains("tokens:       12"),
+        "{}",
+        output.stdout
+    );
+    assert!(
+        output.stdout.contains("declarations: 2"),
+        "{}",
+        output.stdout
+    );
+    assert_eq!(output.code, Some(0));
+}
+
+#[test]
+fn no_run_reports_before_resolving() {
+    let output = script("return 1;", &["--no-run"]);
+    assert!(
+        output.stdout.contains("declarations: 1"),
+        "{}",
+        output.stdout
+    );
+    assert_eq!(output.code, Some(0));
+}
+
+#[test]
+fn debug_env_lists_every_scope_from_the_innermost() {
+    let source = "let outer = 1; { let inner = 2; let other = 3; { debug_env(); } }";
+    let output = script(source, &[]);
+    let lines = output.stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "  [0] ");
+    assert_eq!(lines[1], "  [1] inner, other");
+    assert!(lines[2].starts_with("  [2] "));
+    assert!(lines[2].contains(" outer,"), "{}", lines[2]);
+    assert_eq!(lines.len(), 3);
+}
+
+#[test]
+fn breakpoint_input_is_resolved_in_the_paused_scope() {
+    let source = "
+        let x = 1;
+        fn f() {
+            let x = 2;
+            breakpoint();
+            return x;
+        }
+        println(f());
+        println(x);";
+    let output = script_with_input(source, &["--debug"], "x = x * 10;\nreturn 1;\nc\n");
+    assert!(
+        output
+            .stdout
+            .contains("Can't return from outside a function"),
+        "{}",
+        output.stdout
+    );
+    assert!(output.stdout.ends_with("20\n1\n"), "{}", output.stdout);
+}
+
+#[test]
+fn division_is_exact_by_default() {
+    let output = script("println(5 / 2); println(6 / 3); println(5 /. 2);", &[]);
+    assert_eq!(output.stdout, "2.5\n2\n2.5\n");
+}
+
+#[test]
+fn int_div_truncates_whole_number_division() {
+    let source = "println(5 / 2); println(6 / 3); println(-5 / 2); println(5.5 / 2);";
+    let output = script(source, &["--int-div"]);
+    assert_eq!(output.stdout, "2\n2\n-2\n2.75\n");
+}
+
+#[test]
+fn exact_division_operator_ignores_int_div() {
+    let output = script("println(5 /. 2);", &["--int-div"]);
+    assert_eq!(output.stdout, "2.5\n");
+}
+
+#[test]
+fn repl_prints_values_and_newly_defined_names() {
+    let output = binary(&[], "let x = 2;\nfn f() {}\nx * 21\nx = 3;\nmissing\n");
+    let lines = output.stdout.split("> ").map(str::trim).collect::<Vec<_>>();
+    assert_eq!(lines[1], "defined: x");
+    assert_eq!(lines[2], "defined: f");
+    assert_eq!(lines[3], "42");
+    assert_eq!(lines[4], "3");
+    assert!(lines[5].contains("Undefined variable 'missing'"));
+    assert_eq!(output.code, Some(0));
+}
+
+#[test]
+fn ast_prints_the_tree_without_running() {
+    let source = "fn f(a, ...rest) {\n  return a * (1 + 2);\n}\nprintln(f(1));";
+    let output = script(source, &["--ast"]);
+    assert_eq!(
+        output.stdout,
+        "fn f (a ...rest)\n  return (* a (group (+ 1 2)))\n(call println (call f 1))\n"
+    );
+}
+
+#[test]
+fn ast_is_printed_before_resolving() {
+    let output = script("return 1;", &["--ast"]);
+    assert_eq!(output.stdout, "return 1\n");
+    assert_eq!(output.code, Some(0));
+}
+
+#[test]
+fn syntax_errors_exit_with_65() {
+    let output = script("let = 1;", &[]);
+    assert!(output.stdout.contains("SyntaxError"), "{}", output.stdout);
+    assert_eq!(output.code, Some(65));
+    assert_eq!(script("\"unterminated", &[]).code, Some(65));
+}
+
+#[test]
+fn resolver_errors_exit_with_65() {
+    let output = script("return 1;", &[]);
+    assert!(output
+        .stdout
+        .contains("Can't return from outside a function"));
+    assert_eq!(output.code, Some(65));
+}
+
+#[test]
+fn runtime_errors_exit_with_70() {
+    let output = script("println(1); 1 - \"a\";", &[]);
+    assert!(output.stdout.starts_with("1\n"), "{}", output.stdout);
+    assert_eq!(output.code, Some(70));
+}
+
+#[test]
+fn successful_run_exits_with_0() {
+    assert_eq!(script("println(1);", &[]).code, Some(0));
+}
+
+#[test]
+fn dash_reads_the_program_from_stdin() {
+    let output = binary(&["-"], "println(1+1);");
+    assert_eq!(output.stdout, "2\n");
+    assert_eq!(output.code, Some(0));
+}
+
+#[test]
+fn dash_keeps_the_exit_codes() {
+    assert_eq!(binary(&["-"], "let = ;").code, Some(65));
+    assert_eq!(binary(&["-"], "1 - \"a\";").code, Some(70));
+}
+
+#[test]
+fn debug_env_leaves_out_the_bindings_of_a_for_loop() {
+    let source = "for i in 0..1 { for _ in 0..1 { let x = 2; debug_env(); } }";
+    let output = script(source, &[]);
+    assert!(!output.stdout.contains('@'), "{}", output.stdout);
+    let lines = output.stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "  [0] x");
+    assert_eq!(lines[1], "  [1] ");
+    assert_eq!(lines[3], "  [3] i");
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::{error, value};
+
+#[test]
+fn list_literals_index_and_assign() {
+    assert_eq!(value("[1, \"a\", [true]]"), "[1, a, [true]]");
+    assert_eq!(value("let l = [10, 20, 30]; l[1]"), "20");
+    assert_eq!(value("let l = [10, 20, 30]; l[1] = 5; l"), "[10, 5, 30]");
+}
+
+#[test]
+fn list_index_must_be_an_integer_in_bounds() {
+    assert!(error("[1, 2][2]").contains("out of bounds"));
+    assert!(error("[1, 2][-1]").contains("out of bounds"));
+    assert!(!error("[1, 2][0.5]").is_empty());
+}
+
+#[test]
+fn lists_are_shared_by_reference() {
+    assert_eq!(value("let a = [1]; let b = a; b[0] = 2; a"), "[2]");
+}
+
+#[test]
+fn list_containing_itself_prints() {
+    assert_eq!(value("let a = [1, 2]; a[1] = a; a"), "[1, [...]]");
+    assert_eq!(
+        value("let a = [1, 2]; let b = [a]; a[1] = b; a"),
+        "[1, [[...]]]"
+    );
+}
+
+#[test]
+fn map_containing_itself_prints() {
+    assert_eq!(
+        value("let m = {\"k\": 1}; m[\"self\"] = m; m"),
+        "{\"k\": 1, \"self\": {...}}"
+    );
+}
+
+#[test]
+fn lists_compare_by_value() {
+    assert_eq!(value("[1, [2]] == [1, [2]]"), "true");
+    assert_eq!(value("[1, 2] == [1, 3]"), "false");
+}
+
+#[test]
+fn cyclic_lists_compare_without_recursing_forever() {
+    let source = "
+        let a = [1, 2]; a[1] = a;
+        let b = [1, 2]; b[1] = b;
+        let c = [2, 2]; c[1] = c;
+        [a == a, a == b, a == c]";
+    assert_eq!(value(source), "[true, true, false]");
+}
+
+#[test]
+fn compound_assignment_on_elements() {
+    assert_eq!(value("let l = [1, 2]; l[1] += 10; l"), "[1, 12]");
+    assert_eq!(value("let m = {\"k\": 2}; m[\"k\"] *= 3; m"), "{\"k\": 6}");
+}
+
+#[test]
+fn compound_assignment_evaluates_the_index_once() {
+    let source = "
+        let calls = 0;
+        fn index() { calls = calls + 1; return 0; }
+        let l = [5];
+        l[index()] += 1;
+        [calls, l[0]]";
+    assert_eq!(value(source), "[1, 6]");
+}
+
+#[test]
+fn compound_assignment_evaluates_the_object_once() {
+    let source = "
+        let calls = 0;
+        let l = [5];
+        fn list() { calls = calls + 1; return l; }
+        list()[0] -= 1;
+        [calls, l[0]]";
+    assert_eq!(value(source), "[1, 4]");
+}
+
+#[test]
+fn comprehension_maps_each_item() {
+    assert_eq!(value("[x * x for x in 0..4]"), "[0, 1, 4, 9]");
+    assert_eq!(value("[s + \"!\" for s in [\"a\", \"b\"]]"), "[a!, b!]");
+}
+
+#[test]
+fn comprehension_filters_with_if() {
+    assert_eq!(value("[x for x in 0..10 if x % 3 == 0]"), "[0, 3, 6, 9]");
+}
+
+#[test]
+fn comprehensions_nest() {
+    let source = "[[x * y for y in 1..4] for x in 1..3]";
+    assert_eq!(value(source), "[[1, 2, 3], [2, 4, 6]]");
+}
+
+#[test]
+fn comprehension_variable_does_not_leak() {
+    assert!(error("let l = [x for x in 0..2]; x").contains("Undefined variable 'x'"));
+}
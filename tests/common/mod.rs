@@ -0,0 +1,147 @@
+// Helpers shared by the integration tests. Most tests run source in-process through
+// the same lex, parse, resolve pipeline as the binary; the ones needing command-line
+// flags, stdin or the printed output run the binary itself instead.
+#![allow(dead_code)]
+
+use interp::{
+    error::{ErrorReport, TwliError},
+    interpreter::Interpreter,
+    lexer::Lexer,
+    parser::Parser,
+    printer::pretty_print,
+    resolver::Resolver,
+    runtime::Object,
+};
+use std::{
+    cell::RefCell,
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// as in the REPL, a trailing expression needs no ';'
+pub fn eval_in(interp: &mut Interpreter, source: &str) -> anyhow::Result<Object> {
+    let mut source = source.trim().to_string();
+    if !source.ends_with(';') && !source.ends_with('}') {
+        source.push(';');
+    }
+    let tokens = Lexer::new(source).tokenize()?;
+    let mut declarations = Parser::new(tokens).parse()?;
+    Resolver::new().resolve(&mut declarations)?;
+    interp.interpret_value(declarations)
+}
+
+// the value of the program's trailing expression
+pub fn eval(source: &str) -> anyhow::Result<Object> {
+    eval_in(&mut Interpreter::new(), source)
+}
+
+// the displayed value of the program's trailing expression
+pub fn value(source: &str) -> String {
+    match eval(source) {
+        Ok(value) => value.to_string(),
+        Err(e) => panic!("{source:?} failed: {e}"),
+    }
+}
+
+// the message of the error the program stops with
+pub fn error(source: &str) -> String {
+    match eval(source) {
+        Ok(value) => panic!("{source:?} should have failed, but gave {value}"),
+        Err(e) => e.to_string(),
+    }
+}
+
+// the syntax tree as `--ast` prints it
+pub fn ast(source: &str) -> String {
+    let tokens = Lexer::new(source.to_string()).tokenize().unwrap();
+    let declarations = Parser::new(tokens).parse().unwrap();
+    pretty_print(&declarations)
+}
+
+// the error the program stops with, or the first of those a pass collected
+pub fn failure(source: &str) -> TwliError {
+    let e = match eval(source) {
+        Ok(value) => panic!("{source:?} should have failed, but gave {value}"),
+        Err(e) => e,
+    };
+    match e.downcast::<TwliError>() {
+        Ok(error) => error,
+        Err(e) => match e.downcast::<ErrorReport>() {
+            Ok(report) => report.errors[0].clone(),
+            Err(e) => panic!("{source:?} failed without a position: {e}"),
+        },
+    }
+}
+
+// runs the program as a script, keeping the interpreter around to inspect its globals
+pub fn run(source: &str) -> anyhow::Result<Interpreter> {
+    let mut interp = Interpreter::new();
+    run_in(&mut interp, source)?;
+    Ok(interp)
+}
+
+pub fn run_in(interp: &mut Interpreter, source: &str) -> anyhow::Result<()> {
+    let tokens = Lexer::new(source.to_string()).tokenize()?;
+    let mut declarations = Parser::new(tokens).parse()?;
+    Resolver::new().resolve(&mut declarations)?;
+    interp.interpret(declarations)
+}
+
+pub fn global(interp: &Interpreter, name: &str) -> Option<Object> {
+    RefCell::borrow(&interp.global).local(name).cloned()
+}
+
+// a fresh directory holding the given files, for tests that need a script on disk
+pub fn files(files: &[(&str, &str)]) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "twli-test-{}-{}",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, source) in files {
+        fs::write(dir.join(name), source).unwrap();
+    }
+    dir
+}
+
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+// runs the binary with `args`, feeding it `stdin`
+pub fn binary(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_interp"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // the program may exit without reading all of it
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    Output {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        code: output.status.code(),
+    }
+}
+
+// writes `source` to a script of its own and runs the binary on it
+pub fn script(source: &str, flags: &[&str]) -> Output {
+    script_with_input(source, flags, "")
+}
+
+pub fn script_with_input(source: &str, flags: &[&str], stdin: &str) -> Output {
+    let path = files(&[("main.lox", source)]).join("main.lox");
+    let mut args = vec![path.to_str().unwrap()];
+    args.extend(flags);
+    binary(&args, stdin)
+}
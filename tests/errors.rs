@@ -0,0 +1,166 @@
+mod common;
+
+use common::{error, failure, global, run_in, script};
+use interp::{
+    error::{syntax_error, ErrorReport, TwliError},
+    interpreter::Interpreter,
+};
+
+#[test]
+fn error_inside_a_grouping_points_at_its_line() {
+    let error = failure("let x = (\n  1 +\n  -\"a\"\n);");
+    assert!(matches!(error, TwliError::Runtime { .. }));
+    assert_eq!(error.line(), 3);
+}
+
+#[test]
+fn calling_a_non_callable_points_at_the_callee() {
+    let error = failure("let x = 1;\n(\n  x\n)();");
+    assert_eq!(error.line(), 3);
+}
+
+#[test]
+fn unexpected_token_is_named_in_words() {
+    let error = failure("let x = ;");
+    assert_eq!(error.message(), "Expected expression, found `;`");
+    let error = failure("if (true) { 1; } else if");
+    assert!(error.message().contains("keyword `if`"), "{error}");
+}
+
+#[test]
+fn assigning_to_a_literal_names_it() {
+    let error = failure("5 = 1;");
+    assert!(error
+        .message()
+        .ends_with("cannot assign to a number literal"));
+    let error = failure("true = 1;");
+    assert!(error
+        .message()
+        .ends_with("cannot assign to a boolean literal"));
+}
+
+#[test]
+fn assigning_to_a_call_result_names_it() {
+    let error = failure("fn f() {} f() = 1;");
+    assert!(error
+        .message()
+        .ends_with("cannot assign to the result of a call"));
+}
+
+#[test]
+fn assigning_to_a_grouping_names_it() {
+    let error = failure("let x = 1; (x) = 2;");
+    assert!(error
+        .message()
+        .ends_with("cannot assign to a parenthesized expression"));
+}
+
+#[test]
+fn calling_a_number_names_the_variable_and_type() {
+    let error = failure("let x = 5;\nx();");
+    assert_eq!(
+        error.message(),
+        "Expected callable object, but 'x' is a number"
+    );
+    assert_eq!(error.line(), 2);
+}
+
+#[test]
+fn calling_a_non_variable_names_the_type() {
+    let error = failure("\"text\"();");
+    assert_eq!(error.message(), "Expected callable object, found a string");
+}
+
+#[test]
+fn parser_reports_up_to_the_cap_then_a_summary() {
+    let report = error(&"let = 1;\n".repeat(50));
+    let lines = report.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 21);
+    assert_eq!(lines[20], "... and 30 more errors");
+}
+
+#[test]
+fn lexer_reports_up_to_the_cap_then_a_summary() {
+    let report = error(&"@\n".repeat(50));
+    let lines = report.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 21);
+    assert!(lines[0].contains("Unexpected Token '@'"));
+    assert_eq!(lines[20], "... and 30 more errors");
+}
+
+#[test]
+fn max_errors_flag_changes_the_cap() {
+    let output = script(&"let = 1;\n".repeat(50), &["--max-errors=5"]);
+    let lines = output.stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[5], "... and 45 more errors");
+}
+
+#[test]
+fn for_loop_without_a_block_points_at_the_missing_brace() {
+    let error = failure("for i in 0..3\n  println(i);");
+    assert_eq!(
+        error.message(),
+        "Expected '{' after for loop range, found identifier"
+    );
+    assert_eq!(error, syntax_error(&2, &3, error.message()));
+}
+
+#[test]
+fn undefined_variable_is_a_runtime_error() {
+    let error = failure("let x = 1;\nprintln(y);");
+    assert!(matches!(error, TwliError::Runtime { .. }), "{error:?}");
+    assert_eq!(error.message(), "Undefined variable 'y'");
+    assert_eq!(error.line(), 2);
+}
+
+#[test]
+fn errors_keep_their_kind_and_position() {
+    let error = failure("let x = ;");
+    assert_eq!(
+        error,
+        syntax_error(&1, &9, "Expected expression, found `;`")
+    );
+    let error = failure("1 - \"a\";");
+    assert!(
+        matches!(error, TwliError::Runtime { line: 1, .. }),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn keep_going_reports_every_failing_declaration() {
+    let mut interp = Interpreter::new();
+    interp.keep_going = true;
+    let source = "let a = 1 - \"a\";\nlet ok = 42;\nlet b = missing;";
+    let report = run_in(&mut interp, source).err().unwrap();
+    let report = report.downcast::<ErrorReport>().unwrap();
+
+    assert_eq!(global(&interp, "ok").unwrap().to_string(), "42");
+    let lines = report.errors.iter().map(|e| e.line()).collect::<Vec<_>>();
+    assert_eq!(lines, [1, 3]);
+    assert_eq!(report.errors[1].message(), "Undefined variable 'missing'");
+}
+
+#[test]
+fn keep_going_reports_an_error_from_main() {
+    let mut interp = Interpreter::new();
+    interp.keep_going = true;
+    let source =
+        "let a = 1 - \"a\";\nfn main() {\n  ran = true;\n  1 - \"b\";\n}\nlet ran = false;";
+    let report = run_in(&mut interp, source).err().unwrap();
+    let report = report.downcast::<ErrorReport>().unwrap();
+
+    assert_eq!(global(&interp, "ran").unwrap().to_string(), "true");
+    let lines = report.errors.iter().map(|e| e.line()).collect::<Vec<_>>();
+    assert_eq!(lines, [1, 4]);
+}
+
+#[test]
+fn without_keep_going_the_first_error_stops_the_program() {
+    let mut interp = Interpreter::new();
+    let source = "let a = 1 - \"a\";\nlet ok = 42;";
+    let error = run_in(&mut interp, source).err().unwrap();
+    assert!(error.downcast_ref::<TwliError>().is_some());
+    assert!(global(&interp, "ok").is_none());
+}
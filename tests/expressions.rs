@@ -0,0 +1,121 @@
+mod common;
+
+use common::{error, eval_in, value};
+use interp::interpreter::Interpreter;
+
+#[test]
+fn strings_repeat() {
+    assert_eq!(value("\"ab\" * 3"), "ababab");
+    assert_eq!(value("2 * \"é\""), "éé");
+}
+
+#[test]
+fn huge_repetition_errors_instead_of_allocating() {
+    assert!(error("\"x\" * 1e15").contains("Repetition result too large"));
+}
+
+#[test]
+fn lists_repeat_like_strings() {
+    assert_eq!(value("[1, 2] * 2"), "[1, 2, 1, 2]");
+    assert_eq!(value("3 * [0]"), "[0, 0, 0]");
+    assert_eq!(value("[1] * 0"), "[]");
+    assert_eq!(
+        value("let row = [0]; let grid = [row] * 2; row[0] = 1; grid"),
+        "[[1], [1]]"
+    );
+    assert!(error("[1] * 1e15").contains("Repetition result too large"));
+    assert!(error("[1] * -1").contains("non-negative integer"));
+}
+
+#[test]
+fn repetition_count_must_be_a_whole_number() {
+    assert!(error("\"x\" * -1").contains("non-negative integer"));
+    assert!(error("\"x\" * 1.5").contains("non-negative integer"));
+}
+
+#[test]
+fn unary_operators_chain() {
+    assert_eq!(value("!!true"), "true");
+    assert_eq!(value("--5"), "5");
+    assert_eq!(value("- -5"), "5");
+}
+
+#[test]
+fn pipeline_applies_stages_left_to_right() {
+    let source = "
+        fn double(x) { return x * 2; }
+        fn inc(x) { return x + 1; }
+        3 |> double |> inc";
+    assert_eq!(value(source), "7");
+}
+
+#[test]
+fn pipeline_into_a_non_callable_is_an_error() {
+    assert!(error("3 |> 4").contains("callable"));
+}
+
+#[test]
+fn string_length_counts_characters() {
+    assert_eq!(value("len(\"héllo\")"), "5");
+    assert_eq!(value("len(\"日本語\")"), "3");
+    assert_eq!(value("len(\"🦀\")"), "1");
+}
+
+#[test]
+fn string_index_and_slice_by_character() {
+    assert_eq!(value("\"日本語\"[1]"), "本");
+    assert_eq!(value("\"héllo\"[1..4]"), "éll");
+    assert!(error("\"日本語\"[3]").contains("out of bounds"));
+}
+
+#[test]
+fn multi_byte_strings_concatenate() {
+    assert_eq!(value("\"日本\" + \"語\""), "日本語");
+    assert_eq!(value("len(\"é\" + \"🦀\")"), "2");
+}
+
+#[test]
+fn strict_float_is_a_setting_of_the_interpreter() {
+    let mut interp = Interpreter::new();
+    interp.strict_float = true;
+    let shown = eval_in(&mut interp, "to_string([6 / 2, 1.5, 1..3])").unwrap();
+    assert_eq!(shown.to_string(), "[3.0, 1.5, 1.0..3.0]");
+    assert_eq!(value("to_string([6 / 2, 1.5, 1..3])"), "[3, 1.5, 1..3]");
+}
+
+#[test]
+fn int_div_is_a_setting_of_the_interpreter() {
+    let mut interp = Interpreter::new();
+    interp.int_div = true;
+    let quotients = eval_in(&mut interp, "let x = 7; x /= 2; [5 / 2, -5 / 2, 5 /. 2, x]").unwrap();
+    assert_eq!(quotients.to_string(), "[2, -2, 2.5, 3]");
+    assert_eq!(value("[5 / 2, 5 /. 2]"), "[2.5, 2.5]");
+}
+
+#[test]
+fn max_result_size_is_a_setting_of_the_interpreter() {
+    let mut interp = Interpreter::new();
+    interp.max_result_size = 4;
+    assert_eq!(
+        eval_in(&mut interp, "\"ab\" * 2").unwrap().to_string(),
+        "abab"
+    );
+    let error = eval_in(&mut interp, "\"ab\" * 3").err().unwrap();
+    assert!(error.to_string().contains("too large"), "{error}");
+    let error = eval_in(&mut interp, "collect(0..5)").err().unwrap();
+    assert!(error.to_string().contains("too large"), "{error}");
+    assert_eq!(value("\"ab\" * 3"), "ababab");
+}
+
+#[test]
+fn repetition_limit_counts_the_bytes_of_a_string() {
+    let mut interp = Interpreter::new();
+    interp.max_result_size = 4;
+    assert!(eval_in(&mut interp, "\"é\" * 3").is_err());
+    assert_eq!(eval_in(&mut interp, "\"é\" * 2").unwrap().to_string(), "éé");
+    assert_eq!(
+        eval_in(&mut interp, "[1] * 4").unwrap().to_string(),
+        "[1, 1, 1, 1]"
+    );
+    assert!(eval_in(&mut interp, "[1] * 5").is_err());
+}
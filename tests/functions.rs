@@ -0,0 +1,167 @@
+mod common;
+
+use common::{error, failure, global, run, value};
+use std::time::Instant;
+
+#[test]
+fn return_unwinds_nested_blocks_and_loops() {
+    let source = "
+        fn find(n) {
+            let i = 0;
+            while (true) {
+                { if (i == n) { return i * 10; } }
+                i = i + 1;
+            }
+        }
+        find(3)";
+    assert_eq!(value(source), "30");
+}
+
+#[test]
+fn error_inside_function_is_not_taken_for_a_return() {
+    let source = "
+        fn f() {
+            let x = 1 - \"a\";
+            return 1;
+        }
+        f()";
+    let error = failure(source);
+    assert_eq!(
+        error.message(),
+        "Expected both operands to be numbers in subtraction operation"
+    );
+    assert_eq!(error.line(), 2);
+}
+
+#[test]
+fn function_without_return_gives_null() {
+    assert_eq!(value("fn f() { 1; } f()"), "null");
+}
+
+#[test]
+fn main_is_called_after_every_declaration() {
+    let source = "
+        fn main() { result = helper(); }
+        fn helper() { return 42; }
+        let result = 0;";
+    let interp = run(source).unwrap();
+    assert_eq!(global(&interp, "result").unwrap().to_string(), "42");
+}
+
+#[test]
+fn main_taking_parameters_is_an_error() {
+    let error = run("fn main(args) {}").err().unwrap();
+    assert!(error.to_string().contains("must not take parameters"));
+}
+
+#[test]
+fn script_without_main_runs_its_statements() {
+    let interp = run("let x = 1; x = x + 1;").unwrap();
+    assert_eq!(global(&interp, "x").unwrap().to_string(), "2");
+}
+
+#[test]
+fn function_expression_assigned_to_a_variable() {
+    assert_eq!(
+        value("let add = fn (a, b) { return a + b; }; add(1, 2)"),
+        "3"
+    );
+    assert_eq!(value("let f = fn () {}; f"), "<user fn lambda>");
+}
+
+#[test]
+fn function_expression_closes_over_its_scope() {
+    let source = "
+        fn counter() {
+            let n = 0;
+            return fn () { n = n + 1; return n; };
+        }
+        let c = counter();
+        c();
+        c()";
+    assert_eq!(value(source), "2");
+}
+
+#[test]
+fn rest_parameter_collects_extra_arguments() {
+    assert_eq!(
+        value("fn f(a, ...rest) { return rest; } f(1, 2, 3)"),
+        "[2, 3]"
+    );
+    assert_eq!(value("fn f(a, ...rest) { return rest; } f(1)"), "[]");
+}
+
+#[test]
+fn rest_parameter_still_requires_the_fixed_ones() {
+    let message = error("fn f(a, b, ...rest) {} f(1)");
+    assert!(
+        message.contains("Expected at least 2 argument(s)"),
+        "{message}"
+    );
+}
+
+#[test]
+fn arguments_are_evaluated_left_to_right() {
+    let source = "
+        let log = \"\";
+        fn tick(name) { log = log + name; return name; }
+        fn three(a, b, c) { return a + b + c; }
+        let result = three(tick(\"a\"), tick(\"b\"), tick(\"c\"));
+        log + \" \" + result";
+    assert_eq!(value(source), "abc abc");
+}
+
+#[test]
+fn callee_is_evaluated_before_its_arguments() {
+    let source = "
+        let log = \"\";
+        fn tick(name) { log = log + name; return name; }
+        fn pick() { log = log + \"f\"; return tick; }
+        pick()(tick(\"x\"));
+        log";
+    assert_eq!(value(source), "fxx");
+}
+
+#[test]
+fn later_arguments_see_earlier_side_effects() {
+    let source = "
+        let n = 0;
+        fn next() { n = n + 1; return n; }
+        fn pair(a, b) { return a * 10 + b; }
+        pair(next(), next())";
+    assert_eq!(value(source), "12");
+}
+
+#[test]
+fn tight_recursion_gives_the_same_results() {
+    let source = "
+        fn fib(n) {
+            if (n < 2) { return n; }
+            return fib(n - 1) + fib(n - 2);
+        }
+        fib(20)";
+    assert_eq!(value(source), "6765");
+}
+
+// a timing rather than a check, run with `cargo test --release -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_tight_recursion() {
+    let source = "
+        fn fib(n) {
+            if (n < 2) { return n; }
+            return fib(n - 1) + fib(n - 2);
+        }
+        fib(25)";
+    let start = Instant::now();
+    assert_eq!(value(source), "75025");
+    println!("fib(25) took {:?}", start.elapsed());
+}
+
+#[test]
+fn parameters_are_fresh_on_every_call() {
+    let source = "
+        fn sum(a, b, c) { return a + b + c; }
+        sum(1, 2, 3) * 100 + sum(4, 5, 6)";
+    assert_eq!(value(source), "615");
+}
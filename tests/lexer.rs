@@ -0,0 +1,132 @@
+mod common;
+
+use common::{failure, value};
+use interp::{
+    lexer::Lexer,
+    token::{TokenType, TriviaKind},
+};
+
+#[test]
+fn underscores_separate_digits() {
+    assert_eq!(value("1_000_000"), "1000000");
+    assert_eq!(value("1_0.2_5"), "10.25");
+}
+
+#[test]
+fn misplaced_underscores_are_rejected() {
+    for source in ["1__0", "1_", "1_.5", "1._5", "1_e5", "_5"] {
+        let error = failure(source);
+        assert!(
+            error.message().contains("Misplaced '_'"),
+            "{source}: {error}"
+        );
+    }
+}
+
+#[test]
+fn underscore_names_are_still_identifiers() {
+    assert_eq!(value("let _a = 1; let a_5 = 2; _a + a_5"), "3");
+}
+
+#[test]
+fn comments_between_tokens_of_an_expression() {
+    assert_eq!(value("1 + /* two */ 2"), "3");
+    assert_eq!(value("1 + // two\n 2"), "3");
+}
+
+#[test]
+fn comments_inside_an_argument_list() {
+    assert_eq!(
+        value("fn add(a, b) { return a + b; } add(1, /* b */ 2)"),
+        "3"
+    );
+    assert_eq!(
+        value("fn add(a, b) { return a + b; } add(1 /* a */, 2 // b\n)"),
+        "3"
+    );
+}
+
+#[test]
+fn comment_between_a_function_name_and_its_parenthesis() {
+    assert_eq!(value("fn one() { return 1; } one /* call */ ()"), "1");
+}
+
+#[test]
+fn unterminated_block_comment_is_an_error() {
+    assert!(failure("1 /* never closed")
+        .message()
+        .contains("Unterminated block comment"));
+}
+
+#[test]
+fn trivia_keeps_blank_lines_and_comments_between_declarations() {
+    let mut lexer = Lexer::new("let a = 1;\n\n// second\nlet b = 2; /* end */".to_string());
+    lexer.capture_trivia = true;
+    let tokens = lexer.tokenize().unwrap();
+
+    // leaving out the single spaces between tokens
+    let trivia = lexer
+        .trivia
+        .iter()
+        .filter(|t| t.text != " ")
+        .map(|t| (t.kind.clone(), t.text.as_str(), t.line, t.before))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        trivia,
+        [
+            (TriviaKind::Whitespace, "\n\n", 1, 5),
+            (TriviaKind::Comment, "// second", 3, 5),
+            (TriviaKind::Whitespace, "\n", 3, 5),
+            (TriviaKind::Comment, "/* end */", 4, 10),
+        ]
+    );
+    assert_eq!(tokens[5].lexeme, "let");
+}
+
+#[test]
+fn trivia_is_not_captured_by_default() {
+    let mut lexer = Lexer::new("let a = 1; // note".to_string());
+    lexer.tokenize().unwrap();
+    assert!(lexer.trivia.is_empty());
+}
+
+#[test]
+fn number_followed_by_multi_byte_character_at_the_end() {
+    let types = |source: &str| {
+        let tokens = Lexer::new(source.to_string()).tokenize().unwrap();
+        tokens.into_iter().map(|t| t.ty).collect::<Vec<_>>()
+    };
+    assert_eq!(types("1é"), [TokenType::Number(1.0), TokenType::Identifier]);
+    assert_eq!(
+        types("1.é"),
+        [
+            TokenType::Number(1.0),
+            TokenType::Dot,
+            TokenType::Identifier
+        ]
+    );
+    assert_eq!(
+        types("12日"),
+        [TokenType::Number(12.0), TokenType::Identifier]
+    );
+    assert!(failure("3€").message().contains("Unexpected Token '€'"));
+}
+
+#[test]
+fn radix_literals() {
+    assert_eq!(value("0xff + 0b101"), "260");
+}
+
+#[test]
+fn radix_literal_followed_by_an_invalid_digit_is_rejected() {
+    let error = failure("0b102");
+    assert_eq!(
+        error.message(),
+        "Invalid digit '2' in number literal '0b102'"
+    );
+    let error = failure("0xFG");
+    assert_eq!(
+        error.message(),
+        "Invalid digit 'G' in number literal '0xFG'"
+    );
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{binary, files};
+
+// runs `main.lox` from a directory holding it and the files it imports
+fn run_main(sources: &[(&str, &str)]) -> String {
+    let dir = files(sources);
+    let output = binary(&[dir.join("main.lox").to_str().unwrap()], "");
+    output.stdout
+}
+
+#[test]
+fn imported_function_can_be_called() {
+    let stdout = run_main(&[
+        ("main.lox", "import \"lib.lox\"; println(double(21));"),
+        ("lib.lox", "export fn double(x) { return x * 2; }"),
+    ]);
+    assert_eq!(stdout, "42\n");
+}
+
+#[test]
+fn imports_are_relative_to_the_importing_file() {
+    let dir = files(&[("lib.lox", "export let answer = 42;")]);
+    let nested = files(&[("main.lox", "import \"../lib.lox\"; println(answer);")]);
+    std::fs::rename(&nested, dir.join("nested")).unwrap();
+    let output = binary(&[dir.join("nested/main.lox").to_str().unwrap()], "");
+    assert_eq!(output.stdout, "42\n");
+}
+
+#[test]
+fn cyclic_import_is_an_error() {
+    let stdout = run_main(&[
+        ("main.lox", "import \"a.lox\";"),
+        ("a.lox", "import \"b.lox\";"),
+        ("b.lox", "import \"a.lox\";"),
+    ]);
+    assert!(stdout.contains("Cyclic import of 'a.lox'"), "{stdout}");
+}
+
+#[test]
+fn aliased_import_binds_a_module() {
+    let stdout = run_main(&[
+        (
+            "main.lox",
+            "import \"lib.lox\" as m; println(m.double(2)); println(typeof(double));",
+        ),
+        ("lib.lox", "export fn double(x) { return x * 2; }"),
+    ]);
+    assert!(stdout.starts_with("4\n"), "{stdout}");
+    assert!(stdout.contains("Undefined variable 'double'"), "{stdout}");
+}
+
+#[test]
+fn aliased_import_leaves_globals_of_the_same_name_alone() {
+    let stdout = run_main(&[
+        (
+            "main.lox",
+            "let name = \"main\"; import \"lib.lox\" as m; println(name); println(m.name);",
+        ),
+        ("lib.lox", "export let name = \"lib\";"),
+    ]);
+    assert_eq!(stdout, "main\nlib\n");
+}
+
+#[test]
+fn imported_file_cannot_see_the_importers_globals() {
+    let stdout = run_main(&[
+        ("main.lox", "let secret = 1; import \"lib.lox\" as m;"),
+        ("lib.lox", "println(len(\"abc\")); println(secret);"),
+    ]);
+    assert!(stdout.starts_with("3\n"), "{stdout}");
+    assert!(stdout.contains("Undefined variable 'secret'"), "{stdout}");
+}
+
+#[test]
+fn only_exported_members_are_visible() {
+    let stdout = run_main(&[
+        (
+            "main.lox",
+            "import \"lib.lox\" as m; println(m.public()); println(m.helper());",
+        ),
+        (
+            "lib.lox",
+            "fn helper() { return 1; } export fn public() { return helper() + 1; }",
+        ),
+    ]);
+    assert!(stdout.starts_with("2\n"), "{stdout}");
+    assert!(stdout.contains("helper"), "{stdout}");
+}
+
+#[test]
+fn unexported_members_are_not_imported_into_globals() {
+    let stdout = run_main(&[
+        (
+            "main.lox",
+            "import \"lib.lox\"; println(shown); println(hidden);",
+        ),
+        ("lib.lox", "let hidden = 1; export let shown = 2;"),
+    ]);
+    assert!(stdout.starts_with("2\n"), "{stdout}");
+    assert!(stdout.contains("Undefined variable 'hidden'"), "{stdout}");
+}
+
+#[test]
+fn imported_file_is_resolved_before_running() {
+    let stdout = run_main(&[
+        ("main.lox", "import \"lib.lox\";"),
+        ("lib.lox", "println(\"ran\"); return 1;"),
+    ]);
+    assert!(!stdout.contains("ran"), "{stdout}");
+    assert!(
+        stdout.contains("Can't return from outside a function"),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{ast, failure, value};
+
+#[test]
+fn deeply_nested_parentheses_are_rejected() {
+    let source = format!("{}1{}", "(".repeat(1000), ")".repeat(1000));
+    let error = failure(&source);
+    assert_eq!(error.message(), "Expression too deeply nested");
+}
+
+#[test]
+fn long_power_chain_is_rejected_as_too_deep() {
+    let source = format!("{}1", "1 ** ".repeat(1000));
+    let error = failure(&source);
+    assert_eq!(error.message(), "Expression too deeply nested");
+}
+
+#[test]
+fn power_is_right_associative() {
+    assert_eq!(value("2 ** 3 ** 2"), "512");
+}
+
+#[test]
+fn long_or_chain_parses_without_nesting() {
+    let source = format!("{}true", "false or ".repeat(1000));
+    assert_eq!(value(&source), "true");
+    let source = format!("{}true", "false || ".repeat(1000));
+    assert_eq!(value(&source), "true");
+}
+
+#[test]
+fn range_binds_looser_than_arithmetic() {
+    assert_eq!(ast("1 + 2 .. 3 * 4;"), "(.. (+ 1 2) (* 3 4))");
+}
+
+#[test]
+fn range_binds_tighter_than_logical_operators() {
+    assert_eq!(ast("a or b..c;"), "(or a (.. b c))");
+    assert_eq!(ast("a and b..c;"), "(and a (.. b c))");
+}
+
+#[test]
+fn range_is_assigned_whole() {
+    assert_eq!(ast("x = 0..n;"), "(= x (.. 0 n))");
+}
+
+#[test]
+fn symbol_and_word_operators_share_a_precedence() {
+    assert_eq!(ast("a and b || c;"), "(|| (and a b) c)");
+    assert_eq!(ast("a && b or c;"), "(or (&& a b) c)");
+    assert_eq!(ast("a || b and c;"), "(|| a (and b c))");
+    assert_eq!(ast("a or b && c;"), "(or a (&& b c))");
+}
+
+#[test]
+fn symbol_operators_short_circuit() {
+    let source = "
+        let calls = 0;
+        fn hit() { calls = calls + 1; return true; }
+        false && hit();
+        true || hit();
+        calls";
+    assert_eq!(value(source), "0");
+}
+
+#[test]
+fn nesting_up_to_the_limit_runs() {
+    let source = format!("{}1{}", "(".repeat(30), ")".repeat(30));
+    assert_eq!(value(&source), "1");
+    let source = format!("{}1;{} 2", "{ ".repeat(30), "}".repeat(30));
+    assert_eq!(value(&source), "2");
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{failure, value};
+use interp::error::TwliError;
+
+#[test]
+fn return_at_the_top_level_is_a_syntax_error() {
+    let error = failure("let x = 1;\nreturn 5;");
+    assert!(matches!(error, TwliError::Syntax { .. }));
+    assert_eq!(error.message(), "Can't return from outside a function");
+    assert_eq!(error.line(), 2);
+}
+
+#[test]
+fn return_in_a_top_level_block_is_a_syntax_error() {
+    let error = failure("{ if (true) { return; } }");
+    assert_eq!(error.message(), "Can't return from outside a function");
+}
+
+#[test]
+fn return_inside_methods_and_lambdas_is_allowed() {
+    let source = "
+        class A { fn get() { return 1; } }
+        let f = fn () { return 2; };
+        A().get() + f()";
+    assert_eq!(value(source), "3");
+}
+
+#[test]
+fn this_and_super_outside_a_method_are_resolver_errors() {
+    let error = failure("let x = 1;\nthis;");
+    assert!(matches!(error, TwliError::Syntax { .. }));
+    assert_eq!(
+        error.message(),
+        "Can't use 'this' outside of a class method"
+    );
+    assert_eq!(error.line(), 2);
+
+    let error = failure("fn f() {\n  return super.get();\n}");
+    assert_eq!(
+        error.message(),
+        "Can't use 'super' outside of a class method"
+    );
+    assert_eq!(error.line(), 2);
+}
+
+#[test]
+fn this_is_allowed_in_closures_inside_methods() {
+    let source = "
+        class A {
+            fn init() { this.n = 3; }
+            fn later() { return fn () { return this.n; }; }
+        }
+        A().later()()";
+    assert_eq!(value(source), "3");
+}
+
+#[test]
+fn a_class_body_does_not_leak_into_what_follows() {
+    let error = failure("class A { fn get() { return this; } }\nfn f() { return this; }");
+    assert_eq!(
+        error.message(),
+        "Can't use 'this' outside of a class method"
+    );
+    assert_eq!(error.line(), 2);
+}
+
+#[test]
+fn eval_inside_a_method_sees_this() {
+    let source = "
+        class A {
+            fn init() { this.n = 4; }
+            fn peek() { return eval(\"this.n\"); }
+        }
+        A().peek()";
+    assert_eq!(value(source), "4");
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::{error, eval_in, value};
+use interp::interpreter::Interpreter;
+
+#[test]
+fn discarded_let_still_evaluates_its_initializer() {
+    let source = "
+        let calls = 0;
+        fn f() { calls = calls + 1; return 1; }
+        let _ = f();
+        let _ = f();
+        calls";
+    assert_eq!(value(source), "2");
+}
+
+#[test]
+fn discard_binds_nothing() {
+    assert!(error("let _ = 1; _").contains("'_'"));
+    assert!(error("for _ in 0..2 { _; }").contains("'_'"));
+}
+
+#[test]
+fn discarded_loop_variable_still_iterates() {
+    assert_eq!(value("let n = 0; for _ in 0..10 { n = n + 1; } n"), "10");
+}
+
+#[test]
+fn fractional_range_steps_by_one() {
+    let source = "let seen = \"\"; for i in 0.5..3.5 { seen = seen + to_string(i) + \" \"; } seen";
+    assert_eq!(value(source), "0.5 1.5 2.5 ");
+}
+
+#[test]
+fn range_crosses_zero() {
+    let source = "let seen = \"\"; for i in -3..3 { seen = seen + to_string(i) + \" \"; } seen";
+    assert_eq!(value(source), "-3 -2 -1 0 1 2 ");
+}
+
+#[test]
+fn assignment_in_a_block_updates_the_enclosing_binding() {
+    let source = "
+        let x = 1;
+        fn f() { let x = 10; { x = 20; } return x; }
+        let inner = f();
+        inner * 100 + x";
+    assert_eq!(value(source), "2001");
+}
+
+#[test]
+fn assignment_updates_the_innermost_binding_only() {
+    assert_eq!(value("let x = 1; { let x = 2; x = 3; } x"), "1");
+}
+
+#[test]
+fn let_in_a_block_is_local_to_it() {
+    assert!(error("{ let inner = 1; } inner").contains("Undefined variable 'inner'"));
+    assert_eq!(value("let x = 1; { let x = 2; } x"), "1");
+}
+
+#[test]
+fn let_in_a_function_does_not_leak() {
+    let source = "fn f() { let local = 1; } f(); local";
+    assert!(error(source).contains("Undefined variable 'local'"));
+}
+
+#[test]
+fn undefined_variable_is_an_error_by_default() {
+    assert!(error("missing").contains("Undefined variable 'missing'"));
+}
+
+#[test]
+fn lenient_mode_reads_undefined_variables_as_null() {
+    let mut interp = Interpreter::new();
+    interp.lenient = true;
+    let value = eval_in(&mut interp, "missing").unwrap();
+    assert_eq!(value.to_string(), "null");
+}
+
+#[test]
+fn finally_runs_when_try_returns() {
+    let source = "
+        let cleaned = false;
+        fn f() {
+            try { return 1; } finally { cleaned = true; }
+            return 2;
+        }
+        let result = f();
+        to_string(result) + \" \" + to_string(cleaned)";
+    assert_eq!(value(source), "1 true");
+}
+
+#[test]
+fn finally_runs_after_catch() {
+    let source = "
+        let log = \"\";
+        try { log = log + \"t\"; 1 - \"a\"; } catch { log = log + \"c\"; } finally { log = log + \"f\"; }
+        log";
+    assert_eq!(value(source), "tcf");
+}
+
+#[test]
+fn return_in_finally_overrides() {
+    let source = "
+        fn f() { try { return 1; } finally { return 2; } }
+        f()";
+    assert_eq!(value(source), "2");
+}
+
+#[test]
+fn lenient_mode_reads_through_the_resolved_scope() {
+    let source = "
+        let x = \"g\";
+        let seen = \"\";
+        {
+            fn show() { return x; }
+            let x = \"l\";
+            seen = show();
+        }
+        seen";
+    assert_eq!(value(source), "g");
+    let mut interp = Interpreter::new();
+    interp.lenient = true;
+    assert_eq!(eval_in(&mut interp, source).unwrap().to_string(), "g");
+}
+
+#[test]
+fn lenient_closures_see_their_captured_bindings() {
+    let source = "
+        fn make() {
+            let n = 1;
+            return fn () { return [n, missing]; };
+        }
+        let n = 2;
+        make()()";
+    let mut interp = Interpreter::new();
+    interp.lenient = true;
+    assert_eq!(
+        eval_in(&mut interp, source).unwrap().to_string(),
+        "[1, null]"
+    );
+}